@@ -0,0 +1,286 @@
+use crate::{in_bounds, DIRECTIONS4, DIRECTIONS8};
+use glam::IVec2;
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Index, IndexMut},
+};
+
+/// Rectangular map stored row by row in a single
+/// `Vec`, indexed by `IVec2`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from cells in row order.
+    ///
+    /// # Panics
+    ///
+    /// When `cells` does not hold exactly
+    /// `width * height` values.
+    pub fn new(
+        width: usize,
+        height: usize,
+        cells: Vec<T>,
+    ) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "grid of {width}x{height} needs {} cells",
+            width * height
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Grid of `size` with every cell set to
+    /// `value`.
+    pub fn filled(size: IVec2, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let width = size.x.max(0) as usize;
+        let height = size.y.max(0) as usize;
+        Grid::new(
+            width,
+            height,
+            vec![value; width * height],
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Width and height as a vector.
+    pub fn size(&self) -> IVec2 {
+        IVec2::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        in_bounds(position, self.size())
+    }
+
+    /// Flat index of `position` in the cells, if
+    /// in bounds.
+    pub fn index_of(
+        &self,
+        position: IVec2,
+    ) -> Option<usize> {
+        self.contains(position).then(|| {
+            position.y as usize * self.width
+                + position.x as usize
+        })
+    }
+
+    /// Position of the cell at a flat index.
+    pub fn position_of(&self, index: usize) -> IVec2 {
+        IVec2::new(
+            (index % self.width) as i32,
+            (index / self.width) as i32,
+        )
+    }
+
+    pub fn get(&self, position: IVec2) -> Option<&T> {
+        self.index_of(position)
+            .map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(
+        &mut self,
+        position: IVec2,
+    ) -> Option<&mut T> {
+        self.index_of(position)
+            .map(|index| &mut self.cells[index])
+    }
+
+    /// In-bounds orthogonal neighbours of
+    /// `position`.
+    pub fn neighbors4(
+        &self,
+        position: IVec2,
+    ) -> impl Iterator<Item = (IVec2, &T)> + '_ {
+        self.neighbors(position, &DIRECTIONS4)
+    }
+
+    /// In-bounds orthogonal and diagonal
+    /// neighbours of `position`.
+    pub fn neighbors8(
+        &self,
+        position: IVec2,
+    ) -> impl Iterator<Item = (IVec2, &T)> + '_ {
+        self.neighbors(position, &DIRECTIONS8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        position: IVec2,
+        directions: &'a [IVec2],
+    ) -> impl Iterator<Item = (IVec2, &'a T)> + 'a {
+        directions.iter().filter_map(move |direction| {
+            let next = position + *direction;
+            self.get(next).map(|cell| (next, cell))
+        })
+    }
+
+    /// Every cell with its position, in row
+    /// order.
+    pub fn iter_positions(
+        &self,
+    ) -> impl Iterator<Item = (IVec2, &T)> + '_ {
+        self.cells.iter().enumerate().map(
+            |(index, cell)| (self.position_of(index), cell),
+        )
+    }
+
+    /// Cells in row order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Grid of the same size with `f` applied to
+    /// every cell.
+    pub fn map<U>(
+        &self,
+        f: impl FnMut(&T) -> U,
+    ) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// First position holding `value`, in row
+    /// order.
+    pub fn find(&self, value: T) -> Option<IVec2> {
+        self.cells
+            .iter()
+            .position(|cell| *cell == value)
+            .map(|index| self.position_of(index))
+    }
+
+    /// Every position holding `value`, in row
+    /// order.
+    pub fn find_all(
+        &self,
+        value: T,
+    ) -> impl Iterator<Item = IVec2> + '_ {
+        self.iter_positions().filter_map(
+            move |(position, cell)| {
+                (*cell == value).then_some(position)
+            },
+        )
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: IVec2) -> &T {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "{position} out of bounds for grid of {}",
+                self.size()
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, position: IVec2) -> &mut T {
+        let size = self.size();
+        self.get_mut(position).unwrap_or_else(|| {
+            panic!(
+                "{position} out of bounds for grid of {size}"
+            )
+        })
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn sample() -> Grid<char> {
+        Grid::new(3, 2, "abcdef".chars().collect())
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = sample();
+        assert_eq!(Some(&'a'), grid.get(IVec2::ZERO));
+        assert_eq!(Some(&'f'), grid.get(IVec2::new(2, 1)));
+        assert_eq!(None, grid.get(IVec2::new(3, 0)));
+        assert_eq!(None, grid.get(IVec2::new(0, -1)));
+        assert_eq!('e', grid[IVec2::new(1, 1)]);
+    }
+
+    #[rstest]
+    #[case(IVec2::ZERO, "bd")]
+    #[case(IVec2::new(1, 0), "cea")]
+    #[case(IVec2::new(1, 1), "bfd")]
+    fn test_neighbors4(
+        #[case] position: IVec2,
+        #[case] expected: &str,
+    ) {
+        let neighbors = sample()
+            .neighbors4(position)
+            .map(|(_, cell)| *cell)
+            .collect::<String>();
+        assert_eq!(expected, neighbors);
+    }
+
+    #[test]
+    fn test_neighbors8() {
+        let neighbors = sample()
+            .neighbors8(IVec2::new(1, 0))
+            .map(|(_, cell)| *cell)
+            .collect::<String>();
+        assert_eq!("cfeda", neighbors);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = sample();
+        assert_eq!(Some(IVec2::new(1, 1)), grid.find('e'));
+        assert_eq!(None, grid.find('z'));
+        assert_eq!(
+            vec![IVec2::new(2, 0)],
+            grid.find_all('c').collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("abc\ndef\n", sample().to_string());
+    }
+}
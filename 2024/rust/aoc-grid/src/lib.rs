@@ -0,0 +1,36 @@
+//! Dense character maps shared by the day crates.
+//!
+//! Positions are `IVec2` with `x` the column and
+//! `y` the line, both starting at 0 in the top
+//! left corner.
+use glam::IVec2;
+
+mod grid;
+pub mod parser;
+
+pub use grid::Grid;
+
+/// Orthogonal directions: north, east, south,
+/// west.
+pub const DIRECTIONS4: [IVec2; 4] =
+    [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
+
+/// Orthogonal and diagonal directions, clockwise
+/// from north.
+pub const DIRECTIONS8: [IVec2; 8] = [
+    IVec2::NEG_Y,
+    IVec2::new(1, -1),
+    IVec2::X,
+    IVec2::new(1, 1),
+    IVec2::Y,
+    IVec2::new(-1, 1),
+    IVec2::NEG_X,
+    IVec2::new(-1, -1),
+];
+
+/// Whether `position` lies inside a map of `size`
+/// starting at the origin.
+pub fn in_bounds(position: IVec2, size: IVec2) -> bool {
+    position.cmpge(IVec2::ZERO).all()
+        && position.cmplt(size).all()
+}
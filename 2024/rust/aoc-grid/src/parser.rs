@@ -0,0 +1,131 @@
+//! nom parsers turning a character map into a
+//! [`Grid`].
+use crate::Grid;
+use nom::{
+    character::complete::{line_ending, none_of},
+    combinator::{all_consuming, map_opt, opt},
+    error::{Error, ErrorKind, ParseError},
+    multi::many1,
+    sequence::{preceded, terminated},
+    Err, Finish, IResult, Parser,
+};
+use std::str::FromStr;
+
+/// Parses lines of cells separated by line
+/// endings into a grid, mapping each character
+/// with `cell`.
+///
+/// Parsing stops before the first line ending
+/// that is not followed by a row of accepted
+/// characters, so the grid can be followed by
+/// other sections (e.g. after a blank line). Rows
+/// of different widths fail with
+/// [`ErrorKind::Verify`].
+pub fn grid<'a, T, E, F>(
+    cell: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>, E>
+where
+    E: ParseError<&'a str>,
+    F: Fn(char) -> Option<T>,
+{
+    move |input: &'a str| {
+        let mut row =
+            many1(map_opt(none_of("\r\n"), &cell));
+
+        let (mut input, mut cells) = row.parse(input)?;
+        let width = cells.len();
+        let mut height = 1;
+
+        while let Ok((rest, next)) =
+            preceded(line_ending::<_, E>, &mut row)
+                .parse(input)
+        {
+            if next.len() != width {
+                return Err(Err::Failure(
+                    E::from_error_kind(
+                        input,
+                        ErrorKind::Verify,
+                    ),
+                ));
+            }
+            cells.extend(next);
+            height += 1;
+            input = rest;
+        }
+
+        Ok((input, Grid::new(width, height, cells)))
+    }
+}
+
+/// Parses a grid keeping every character as is.
+pub fn char_grid<'a, E: ParseError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Grid<char>, E> {
+    grid(Some)(input)
+}
+
+/// Parses a whole input made only of the map,
+/// with an optional trailing line ending.
+impl FromStr for Grid<char> {
+    type Err = Error<String>;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        all_consuming(terminated(
+            char_grid,
+            opt(line_ending),
+        ))(input)
+        .finish()
+        .map(|(_, grid)| grid)
+        .map_err(|err: Error<&str>| {
+            Error::new(err.input.to_string(), err.code)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::IVec2;
+
+    #[test]
+    fn test_char_grid() {
+        let (rest, grid) =
+            char_grid::<Error<_>>("#.#\n.S.\r\n#..\n")
+                .unwrap();
+        assert_eq!("\n", rest);
+        assert_eq!(IVec2::new(3, 3), grid.size());
+        assert_eq!(Some(IVec2::new(1, 1)), grid.find('S'));
+    }
+
+    #[test]
+    fn test_grid_stops_before_next_section() {
+        let (rest, grid) =
+            grid::<_, Error<_>, _>(|ch| ch.to_digit(10))(
+                "012\n345\n\n<>",
+            )
+            .unwrap();
+        assert_eq!("\n\n<>", rest);
+        assert_eq!(Some(&5), grid.get(IVec2::new(2, 1)));
+    }
+
+    #[test]
+    fn test_from_str() {
+        let grid: Grid<char> = "ab\ncd\n".parse().unwrap();
+        assert_eq!("ab\ncd\n", grid.to_string());
+        assert!("ab\ncd\n\nef"
+            .parse::<Grid<char>>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_grid_rejects_ragged_rows() {
+        let result = char_grid::<Error<_>>("abc\nde\nfgh");
+        assert_eq!(
+            Err(Err::Failure(Error::new(
+                "\nde\nfgh",
+                ErrorKind::Verify
+            ))),
+            result
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Runtime loading of puzzle inputs for the day
+//! binaries and benchmarks.
+//!
+//! Inputs are never embedded at compile time so a
+//! fresh clone without `input1.txt`/`input2.txt`
+//! still builds, and switching between example
+//! and real input does not need a rebuild.
+use clap::Parser;
+use miette::{miette, IntoDiagnostic, WrapErr};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Command line arguments selecting the puzzle
+/// input. Flatten it into a binary's own
+/// arguments when it takes more flags.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct InputArgs {
+    /// puzzle input to read, `-` for stdin;
+    /// defaults to `inputN.txt` next to the crate
+    #[clap(short, long)]
+    pub input: Option<PathBuf>,
+}
+
+impl InputArgs {
+    /// Reads the selected input, falling back to
+    /// the default `inputN.txt` of `crate_dir`.
+    pub fn read(
+        &self,
+        crate_dir: impl AsRef<Path>,
+        part: u8,
+    ) -> miette::Result<String> {
+        match &self.input {
+            Some(path) => read(path),
+            None => read(default_path(crate_dir, part)),
+        }
+    }
+}
+
+/// Solve a part of the day's puzzle.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args<P: clap::Args> {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: P,
+}
+
+/// Binaries without puzzle parameters.
+#[derive(clap::Args, Debug)]
+struct NoParams {}
+
+/// Parses `--input` from the command line and
+/// reads the puzzle input for `part`.
+///
+/// ```no_run
+/// let input =
+///     aoc_input::load(env!("CARGO_MANIFEST_DIR"), 1)?;
+/// # Ok::<(), miette::Report>(())
+/// ```
+pub fn load(
+    crate_dir: impl AsRef<Path>,
+    part: u8,
+) -> miette::Result<String> {
+    load_with::<NoParams>(crate_dir, part)
+        .map(|(input, _)| input)
+}
+
+/// Like [`load`], also parsing the day's puzzle
+/// parameters `P` from the remaining flags.
+///
+/// ```no_run
+/// #[derive(clap::Args)]
+/// struct Params {
+///     #[clap(long, default_value_t = 25)]
+///     depth: usize,
+/// }
+///
+/// let (input, params) = aoc_input::load_with::<Params>(
+///     env!("CARGO_MANIFEST_DIR"),
+///     2,
+/// )?;
+/// # Ok::<(), miette::Report>(())
+/// ```
+pub fn load_with<P: clap::Args>(
+    crate_dir: impl AsRef<Path>,
+    part: u8,
+) -> miette::Result<(String, P)> {
+    let args = Args::<P>::parse();
+    let input = args.input.read(crate_dir, part)?;
+    Ok((input, args.params))
+}
+
+/// `inputN.txt` inside the day's crate directory.
+pub fn default_path(
+    crate_dir: impl AsRef<Path>,
+    part: u8,
+) -> PathBuf {
+    crate_dir.as_ref().join(format!("input{part}.txt"))
+}
+
+/// Reads a puzzle input file, or stdin when the
+/// path is `-`.
+pub fn read(
+    path: impl AsRef<Path>,
+) -> miette::Result<String> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .into_diagnostic()
+            .wrap_err("reading puzzle input from stdin")?;
+        return Ok(input);
+    }
+
+    if !path.exists() {
+        let day = path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .filter(|name| name.starts_with("day-"))
+            .unwrap_or("day-XX");
+        return Err(miette!(
+            code = "aoc::input::missing",
+            help = format!(
+                "download it with `just get-input {day}` \
+                 or pass `--input <path>` (`-` for stdin)"
+            ),
+            "puzzle input `{}` not found",
+            path.display()
+        ));
+    }
+
+    std::fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!(
+                "reading puzzle input `{}`",
+                path.display()
+            )
+        })
+}
+
+/// Input for a benchmark, or `None` with a note
+/// on stderr when it has not been downloaded so
+/// the benchmark is skipped instead of failing.
+pub fn bench_input(
+    crate_dir: impl AsRef<Path>,
+    part: u8,
+) -> Option<String> {
+    let path = default_path(crate_dir, part);
+    match read(&path) {
+        Ok(input) => Some(input),
+        Err(_) => {
+            eprintln!(
+                "skipping part {part}: `{}` not found",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+/// Example input for a test: `exampleN.txt` next
+/// to the crate when it has been extracted with
+/// `just get-examples`, `fallback` otherwise.
+pub fn example(
+    crate_dir: impl AsRef<Path>,
+    part: u8,
+    fallback: &str,
+) -> String {
+    let path = crate_dir
+        .as_ref()
+        .join(format!("example{part}.txt"));
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|_| fallback.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            Path::new("day-07/input2.txt"),
+            default_path("day-07", 2)
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let report = read("/nonexistent/day-07/input1.txt")
+            .unwrap_err();
+        assert!(report
+            .help()
+            .unwrap()
+            .to_string()
+            .contains("just get-input day-07"));
+        assert!(bench_input("/nonexistent", 1).is_none());
+    }
+
+    #[test]
+    fn test_read() -> miette::Result<()> {
+        let input = InputArgs {
+            input: Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/Cargo.toml"
+            ))),
+        }
+        .read("/nonexistent", 1)?;
+        assert!(input.contains("name = \"aoc-input\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_params_flags() {
+        #[derive(clap::Args, Debug)]
+        struct Params {
+            #[clap(long, default_value_t = 25)]
+            depth: usize,
+        }
+
+        let args = Args::<Params>::try_parse_from([
+            "part2", "--depth", "2", "-i", "-",
+        ])
+        .unwrap();
+        assert_eq!(2, args.params.depth);
+        assert_eq!(
+            Some(PathBuf::from("-")),
+            args.input.input
+        );
+
+        let args =
+            Args::<Params>::try_parse_from(["part2"])
+                .unwrap();
+        assert_eq!(25, args.params.depth);
+    }
+
+    #[test]
+    fn test_example_fallback() {
+        assert_eq!(
+            "1 2",
+            example("/nonexistent", 1, "1 2")
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Parse errors of the day crates as miette
+//! diagnostics.
+//!
+//! A nom error only holds the input left when the
+//! parser failed. [`ParseError`] puts it back in
+//! the whole puzzle input so the report shows the
+//! offending line and column, with the `context`
+//! stack of a [`VerboseError`] as extra labels.
+//!
+//! ```
+//! use aoc_parse::{IResult, ParseError};
+//! use nom::{
+//!     character::complete::u32, error::context,
+//! };
+//!
+//! fn parse(input: &str) -> IResult<u32> {
+//!     context("depth", u32)(input)
+//! }
+//!
+//! let input = "deep";
+//! let report = parse(input)
+//!     .map_err(|err| ParseError::new(input, err))
+//!     .unwrap_err();
+//! assert_eq!(
+//!     "invalid puzzle input at line 1, column 1: \
+//!      expected digits",
+//!     report.to_string()
+//! );
+//! ```
+use miette::{
+    Diagnostic, LabeledSpan, NamedSource, SourceSpan,
+};
+use nom::error::{ErrorKind, VerboseErrorKind};
+use thiserror::Error;
+
+pub use nom::error::VerboseError;
+
+/// Error type of the day parsers, keeping every
+/// `context` the failure went through.
+pub type Error<'a> = VerboseError<&'a str>;
+
+/// Result of the day parsers.
+pub type IResult<'a, O> =
+    nom::IResult<&'a str, O, Error<'a>>;
+
+/// One step of a nom error, innermost first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// length of the input left at this step
+    pub remaining: usize,
+    pub description: String,
+    /// whether the step comes from `context`
+    pub is_context: bool,
+}
+
+/// nom errors that can be placed back in the
+/// input they were produced from.
+pub trait ErrorStack {
+    fn frames(&self) -> Vec<Frame>;
+}
+
+impl<I: AsRef<str>> ErrorStack for nom::error::Error<I> {
+    fn frames(&self) -> Vec<Frame> {
+        vec![Frame {
+            remaining: self.input.as_ref().len(),
+            description: describe(self.code),
+            is_context: false,
+        }]
+    }
+}
+
+impl<I: AsRef<str>> ErrorStack for VerboseError<I> {
+    fn frames(&self) -> Vec<Frame> {
+        self.errors
+            .iter()
+            .map(|(input, kind)| {
+                let (description, is_context) = match kind {
+                    VerboseErrorKind::Context(context) => {
+                        (context.to_string(), true)
+                    }
+                    VerboseErrorKind::Char(ch) => {
+                        (format!("expected {ch:?}"), false)
+                    }
+                    VerboseErrorKind::Nom(kind) => {
+                        (describe(*kind), false)
+                    }
+                };
+                Frame {
+                    remaining: input.as_ref().len(),
+                    description,
+                    is_context,
+                }
+            })
+            .collect()
+    }
+}
+
+impl<E: ErrorStack> ErrorStack for nom::Err<E> {
+    fn frames(&self) -> Vec<Frame> {
+        match self {
+            nom::Err::Incomplete(_) => vec![Frame {
+                remaining: 0,
+                description: "unexpected end of input"
+                    .to_string(),
+                is_context: false,
+            }],
+            nom::Err::Error(err)
+            | nom::Err::Failure(err) => err.frames(),
+        }
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Tag => "unexpected text".to_string(),
+        ErrorKind::Char => {
+            "unexpected character".to_string()
+        }
+        ErrorKind::Digit => "expected digits".to_string(),
+        ErrorKind::Eof => {
+            "unexpected trailing input".to_string()
+        }
+        ErrorKind::CrLf => {
+            "expected a line ending".to_string()
+        }
+        ErrorKind::Verify
+        | ErrorKind::MapRes
+        | ErrorKind::MapOpt => "invalid value".to_string(),
+        ErrorKind::Many1
+        | ErrorKind::SeparatedList
+        | ErrorKind::Many1Count => {
+            "expected at least one item".to_string()
+        }
+        kind => {
+            format!("expected {}", kind.description())
+        }
+    }
+}
+
+/// A parse failure located in the puzzle input.
+///
+/// An error whose input is not a suffix of the
+/// puzzle input cannot be placed in it, it is
+/// reported without a location.
+#[derive(Debug, Error, Diagnostic)]
+#[error("invalid puzzle input{location}: {label}")]
+#[diagnostic(code(aoc::parse))]
+pub struct ParseError {
+    #[source_code]
+    input: NamedSource<String>,
+    #[label("{label}")]
+    span: Option<SourceSpan>,
+    label: String,
+    /// ` at line _, column _` when located
+    location: String,
+    /// where each enclosing `context` started
+    #[label(collection)]
+    contexts: Vec<LabeledSpan>,
+    #[help]
+    help: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl ParseError {
+    /// Locates `err`, produced while parsing
+    /// `input`, in it.
+    pub fn new(input: &str, err: impl ErrorStack) -> Self {
+        let frames = err.frames();
+        let offset_of = |frame: &Frame| {
+            input.len().checked_sub(frame.remaining).filter(
+                |offset| input.is_char_boundary(*offset),
+            )
+        };
+
+        let failure = frames
+            .iter()
+            .find(|frame| !frame.is_context)
+            .or(frames.first());
+        let offset = match failure {
+            Some(frame) => offset_of(frame),
+            None => Some(input.len()),
+        };
+        let span = offset.map(|offset| {
+            let token = input[offset..]
+                .split(char::is_whitespace)
+                .next()
+                .unwrap_or_default();
+            SourceSpan::from((offset, token.len()))
+        });
+
+        let contexts = frames
+            .iter()
+            .filter(|frame| frame.is_context)
+            .collect::<Vec<_>>();
+        let help = (!contexts.is_empty()).then(|| {
+            let stack = contexts
+                .iter()
+                .rev()
+                .map(|frame| frame.description.as_str())
+                .collect::<Vec<_>>()
+                .join(" > ");
+            format!("while parsing {stack}")
+        });
+
+        let (line, column) = offset
+            .map(|offset| {
+                let before = &input[..offset];
+                let line = before.matches('\n').count() + 1;
+                let column = before
+                    .rsplit('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .count()
+                    + 1;
+                (line, column)
+            })
+            .unzip();
+        let location = line
+            .zip(column)
+            .map(|(line, column)| {
+                format!(" at line {line}, column {column}")
+            })
+            .unwrap_or_default();
+
+        ParseError {
+            input: NamedSource::new(
+                "puzzle input",
+                input.to_string(),
+            ),
+            span,
+            label: failure.map_or_else(
+                || "invalid input".to_string(),
+                |frame| frame.description.clone(),
+            ),
+            location,
+            contexts: contexts
+                .iter()
+                .filter_map(|frame| {
+                    let offset = offset_of(frame)?;
+                    Some(LabeledSpan::at_offset(
+                        offset,
+                        format!("in {}", frame.description),
+                    ))
+                })
+                .collect(),
+            help,
+            line,
+            column,
+        }
+    }
+
+    /// Byte offset of the failure in the input,
+    /// `None` when it could not be placed.
+    pub fn offset(&self) -> Option<usize> {
+        self.span.map(|span| span.offset())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{self, line_ending},
+        combinator::{all_consuming, cut},
+        error::context,
+        multi::separated_list1,
+        sequence::{preceded, separated_pair},
+    };
+
+    fn robot(input: &str) -> IResult<(u32, u32)> {
+        context(
+            "robot",
+            separated_pair(
+                preceded(tag("p="), complete::u32),
+                tag(","),
+                cut(context("velocity", complete::u32)),
+            ),
+        )(input)
+    }
+
+    fn robots(input: &str) -> IResult<Vec<(u32, u32)>> {
+        all_consuming(separated_list1(line_ending, robot))(
+            input,
+        )
+    }
+
+    #[test]
+    fn test_verbose_error() {
+        let input = "p=1,2\np=3,x4\n";
+        let err = ParseError::new(
+            input,
+            robots(input).unwrap_err(),
+        );
+        assert_eq!(
+            (Some(2), Some(5)),
+            (err.line, err.column)
+        );
+        assert_eq!(input.find('x'), err.offset());
+        assert_eq!(
+            "invalid puzzle input at line 2, column 5: \
+             expected digits",
+            err.to_string()
+        );
+        assert_eq!(
+            Some("while parsing robot > velocity"),
+            err.help.as_deref()
+        );
+        assert_eq!(
+            vec![input.find('x').unwrap(), 6],
+            err.contexts
+                .iter()
+                .map(|label| label.offset())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_simple_error() {
+        let input = "12\n34 56\n";
+        let err: nom::Err<nom::error::Error<&str>> =
+            all_consuming(separated_list1(
+                line_ending,
+                complete::u32,
+            ))(input)
+            .unwrap_err();
+        let err = ParseError::new(input, err);
+        assert_eq!(
+            (Some(2), Some(3)),
+            (err.line, err.column)
+        );
+        assert_eq!("unexpected trailing input", err.label);
+        assert_eq!(None, err.help);
+    }
+
+    #[test]
+    fn test_owned_error() {
+        let input = "ab\ncd\n\nef";
+        let err = nom::error::Error::new(
+            "\n\nef".to_string(),
+            ErrorKind::Eof,
+        );
+        let err = ParseError::new(input, err);
+        assert_eq!(
+            (Some(2), Some(3)),
+            (err.line, err.column)
+        );
+    }
+
+    #[test]
+    fn test_foreign_error() {
+        let input = "é!";
+        // longer than the input, and ending inside
+        // the multibyte `é`
+        for remaining in ["ab\ncd", "\u{a9}"] {
+            let err = ParseError::new(
+                input,
+                nom::error::Error::new(
+                    remaining,
+                    ErrorKind::Tag,
+                ),
+            );
+            assert_eq!(None, err.offset());
+            assert_eq!(
+                (None, None),
+                (err.line, err.column)
+            );
+            assert!(err.labels().is_none_or(
+                |mut labels| labels.next().is_none()
+            ));
+            assert_eq!(
+                "invalid puzzle input: unexpected text",
+                err.to_string()
+            );
+        }
+    }
+}
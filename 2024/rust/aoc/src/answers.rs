@@ -0,0 +1,281 @@
+//! Regression checks against the answers recorded
+//! in each day's `answers.toml`.
+//!
+//! The file is the one `just submit` keeps up to
+//! date; only the accepted `part1`/`part2`
+//! answers are read, the logged attempts are
+//! ignored.
+use crate::{
+    registry::Solution,
+    runner::{self, render},
+};
+use miette::{miette, IntoDiagnostic, WrapErr};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Accepted answers of a day.
+#[derive(Debug, Default, Deserialize)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    /// Reads the answers of a day, `None` when
+    /// the file does not exist.
+    pub fn load(
+        path: &Path,
+    ) -> miette::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("reading `{}`", path.display())
+            })?;
+        toml::from_str(&content)
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("parsing `{}`", path.display())
+            })
+            .map(Some)
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Location of a day's answers inside the
+/// workspace, `<root>/day-XX/answers.toml`.
+pub fn answers_path(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("day-{day:02}/answers.toml"))
+}
+
+/// How a part compares to its recorded answer.
+#[derive(Debug)]
+pub enum Status {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// `process` or reading the answers failed
+    Failed(miette::Report),
+    /// nothing to compare, with the reason
+    Skipped(&'static str),
+}
+
+/// Result of checking a single part.
+#[derive(Debug)]
+pub struct Check {
+    pub solution: &'static Solution,
+    pub status: Status,
+}
+
+/// Runs a part against its input and compares the
+/// answer with the recorded one. Parts without an
+/// input or a recorded answer are skipped without
+/// running.
+pub fn check(
+    root: &Path,
+    solution: &'static Solution,
+) -> Check {
+    if !runner::input_path(root, solution).exists() {
+        return Check {
+            solution,
+            status: Status::Skipped("no input"),
+        };
+    }
+
+    let status = match expected(root, solution) {
+        Ok(None) => Status::Skipped("no answer"),
+        Err(err) => Status::Failed(err),
+        Ok(Some(expected)) => {
+            match runner::run(root, solution).answer {
+                Ok(actual) if actual.trim() == expected => {
+                    Status::Match
+                }
+                Ok(actual) => {
+                    Status::Mismatch { expected, actual }
+                }
+                Err(err) => Status::Failed(err),
+            }
+        }
+    };
+    Check { solution, status }
+}
+
+fn expected(
+    root: &Path,
+    solution: &Solution,
+) -> miette::Result<Option<String>> {
+    let answers =
+        Answers::load(&answers_path(root, solution.day))?;
+    Ok(answers.and_then(|answers| {
+        answers
+            .get(solution.part)
+            .map(|answer| answer.trim().to_string())
+    }))
+}
+
+/// Table of the parts that did not match,
+/// followed by a count of each status.
+pub fn report(checks: &[Check]) -> String {
+    let header =
+        ["Day", "Part", "Name", "Expected", "Actual"]
+            .map(String::from);
+    let mut rows = vec![header];
+    for check in checks {
+        let (expected, actual) = match &check.status {
+            Status::Mismatch { expected, actual } => {
+                (expected.clone(), actual.clone())
+            }
+            Status::Failed(_) => {
+                ("?".to_string(), "ERROR".to_string())
+            }
+            Status::Match | Status::Skipped(_) => continue,
+        };
+        rows.push([
+            format!("{:02}", check.solution.day),
+            check.solution.part.to_string(),
+            check.solution.name.to_string(),
+            expected,
+            actual,
+        ]);
+    }
+
+    let count = |matches: fn(&Status) -> bool| {
+        checks
+            .iter()
+            .filter(|check| matches(&check.status))
+            .count()
+    };
+    let summary = format!(
+        "{} matched, {} mismatched, {} failed, {} \
+         skipped",
+        count(|status| matches!(status, Status::Match)),
+        count(|status| {
+            matches!(status, Status::Mismatch { .. })
+        }),
+        count(|status| matches!(status, Status::Failed(_))),
+        count(|status| {
+            matches!(status, Status::Skipped(_))
+        }),
+    );
+
+    if rows.len() == 1 {
+        format!("{summary}\n")
+    } else {
+        format!(
+            "{}\n{summary}\n",
+            render(&rows, &[], false)
+        )
+    }
+}
+
+/// Fails with a summary when any part did not
+/// match its recorded answer.
+pub fn verify(checks: &[Check]) -> miette::Result<()> {
+    let wrong = checks
+        .iter()
+        .filter(|check| {
+            matches!(
+                check.status,
+                Status::Mismatch { .. } | Status::Failed(_)
+            )
+        })
+        .count();
+    if wrong > 0 {
+        return Err(miette!(
+            "{wrong} of {} part(s) do not match their \
+             recorded answer",
+            checks.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    static ECHO: [Solution; 3] = [
+        Solution {
+            day: 1,
+            part: 1,
+            name: "Echo",
+            process: |input| Ok(input.trim().to_string()),
+        },
+        Solution {
+            day: 1,
+            part: 2,
+            name: "Echo",
+            process: |input| Ok(input.trim().to_string()),
+        },
+        Solution {
+            day: 2,
+            part: 1,
+            name: "Unrecorded",
+            process: |_| Err(miette!("nope")),
+        },
+    ];
+
+    #[test]
+    fn test_check() {
+        let root = std::env::temp_dir().join(format!(
+            "aoc-answers-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("day-01")).unwrap();
+        fs::create_dir_all(root.join("day-02")).unwrap();
+        fs::write(root.join("day-01/input1.txt"), "42\n")
+            .unwrap();
+        fs::write(root.join("day-02/input1.txt"), "")
+            .unwrap();
+        fs::write(
+            answers_path(&root, 1),
+            "part1 = \"41\"\npart2 = \"7\"\n\n\
+             [[attempt]]\npart = 1\nanswer = \"40\"\n\
+             verdict = \"too-low\"\ntimestamp = 0\n",
+        )
+        .unwrap();
+
+        let checks = ECHO
+            .iter()
+            .map(|solution| check(&root, solution))
+            .collect::<Vec<_>>();
+
+        assert!(matches!(
+            &checks[0].status,
+            Status::Mismatch { expected, actual }
+                if expected == "41" && actual == "42"
+        ));
+        assert!(matches!(
+            checks[1].status,
+            Status::Skipped("no input")
+        ));
+        assert!(matches!(
+            checks[2].status,
+            Status::Skipped("no answer")
+        ));
+        assert_eq!(
+            "Day | Part | Name | Expected | Actual\n\
+             ----+------+------+----------+-------\n\
+             01  | 1    | Echo | 41       | 42\n\
+             \n\
+             0 matched, 1 mismatched, 0 failed, 2 \
+             skipped\n",
+            report(&checks)
+        );
+        assert!(verify(&checks).is_err());
+        assert!(verify(&checks[1..]).is_ok());
+    }
+}
@@ -0,0 +1,361 @@
+//! Repeated timing of the registered solutions,
+//! with results saved as JSON so a later run can
+//! be compared against a baseline.
+use crate::{
+    registry::{Solution, SOLUTIONS},
+    runner::{self, render},
+};
+use miette::{miette, IntoDiagnostic, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Timing statistics of one part, in nanoseconds.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: u8,
+    pub median_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub samples: usize,
+}
+
+impl Measurement {
+    /// Statistics of a non empty list of sample
+    /// times.
+    pub fn new(
+        solution: &Solution,
+        mut times: Vec<Duration>,
+    ) -> Self {
+        assert!(!times.is_empty(), "no samples");
+        times.sort();
+        let n = times.len();
+        let median = if n % 2 == 0 {
+            (times[n / 2 - 1] + times[n / 2]) / 2
+        } else {
+            times[n / 2]
+        };
+        let ns = |duration: Duration| {
+            duration
+                .as_nanos()
+                .try_into()
+                .unwrap_or(u64::MAX)
+        };
+
+        Measurement {
+            day: solution.day,
+            part: solution.part,
+            median_ns: ns(median),
+            min_ns: ns(times[0]),
+            max_ns: ns(times[n - 1]),
+            samples: n,
+        }
+    }
+}
+
+fn name_of(day: u8, part: u8) -> &'static str {
+    SOLUTIONS
+        .iter()
+        .find(|solution| {
+            (solution.day, solution.part) == (day, part)
+        })
+        .map_or("?", |solution| solution.name)
+}
+
+/// Runs a part against its input up to `samples`
+/// times, stopping early once `budget` is spent,
+/// after one untimed warm up run.
+pub fn measure(
+    root: &Path,
+    solution: &Solution,
+    samples: usize,
+    budget: Duration,
+) -> miette::Result<Measurement> {
+    let input = aoc_input::read(runner::input_path(
+        root, solution,
+    ))?;
+    let process = || {
+        (solution.process)(black_box(&input)).wrap_err_with(
+            || {
+                format!(
+                    "process day {:02} part {}",
+                    solution.day, solution.part
+                )
+            },
+        )
+    };
+
+    process()?;
+    let mut times = Vec::with_capacity(samples);
+    let start = Instant::now();
+    while times.len() < samples.max(1)
+        && (times.is_empty() || start.elapsed() < budget)
+    {
+        let sample = Instant::now();
+        black_box(process()?);
+        times.push(sample.elapsed());
+    }
+
+    Ok(Measurement::new(solution, times))
+}
+
+fn format_ns(ns: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns))
+}
+
+/// Renders measurements as an aligned text table.
+pub fn table(measurements: &[Measurement]) -> String {
+    let header = [
+        "Day", "Part", "Name", "Median", "Min", "Max",
+        "Samples",
+    ]
+    .map(String::from);
+    let mut rows = vec![header];
+    for measurement in measurements {
+        rows.push([
+            format!("{:02}", measurement.day),
+            measurement.part.to_string(),
+            name_of(measurement.day, measurement.part)
+                .to_string(),
+            format_ns(measurement.median_ns),
+            format_ns(measurement.min_ns),
+            format_ns(measurement.max_ns),
+            measurement.samples.to_string(),
+        ]);
+    }
+    render(&rows, &[3, 4, 5, 6], false)
+}
+
+pub fn save(
+    path: &Path,
+    measurements: &[Measurement],
+) -> miette::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .into_diagnostic()?;
+    }
+    let json = serde_json::to_string_pretty(measurements)
+        .into_diagnostic()?;
+    std::fs::write(path, json + "\n")
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!("writing `{}`", path.display())
+        })
+}
+
+pub fn load(
+    path: &Path,
+) -> miette::Result<Vec<Measurement>> {
+    let json = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!(
+                "reading baseline `{}`, save one with \
+                 `--save-baseline`",
+                path.display()
+            )
+        })?;
+    serde_json::from_str(&json)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!("parsing `{}`", path.display())
+        })
+}
+
+/// Median of a part in the baseline and in the
+/// current run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub day: u8,
+    pub part: u8,
+    pub baseline_ns: Option<u64>,
+    pub current_ns: u64,
+}
+
+impl Delta {
+    /// Change of the median in percent, positive
+    /// when slower.
+    pub fn percent(&self) -> Option<f64> {
+        self.baseline_ns.filter(|ns| *ns > 0).map(|ns| {
+            (self.current_ns as f64 - ns as f64) * 100.0
+                / ns as f64
+        })
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.percent()
+            .is_some_and(|percent| percent > threshold)
+    }
+}
+
+/// Pairs every current measurement with its
+/// baseline, if any.
+pub fn compare(
+    baseline: &[Measurement],
+    current: &[Measurement],
+) -> Vec<Delta> {
+    current
+        .iter()
+        .map(|measurement| Delta {
+            day: measurement.day,
+            part: measurement.part,
+            baseline_ns: baseline
+                .iter()
+                .find(|base| {
+                    (base.day, base.part)
+                        == (
+                            measurement.day,
+                            measurement.part,
+                        )
+                })
+                .map(|base| base.median_ns),
+            current_ns: measurement.median_ns,
+        })
+        .collect()
+}
+
+/// Renders the deltas, flagging the ones slower
+/// than `threshold` percent.
+pub fn delta_table(
+    deltas: &[Delta],
+    threshold: f64,
+) -> String {
+    let header = [
+        "Day", "Part", "Name", "Baseline", "Current",
+        "Delta", "Status",
+    ]
+    .map(String::from);
+    let mut rows = vec![header];
+    for delta in deltas {
+        rows.push([
+            format!("{:02}", delta.day),
+            delta.part.to_string(),
+            name_of(delta.day, delta.part).to_string(),
+            delta
+                .baseline_ns
+                .map_or("-".to_string(), format_ns),
+            format_ns(delta.current_ns),
+            delta
+                .percent()
+                .map_or("-".to_string(), |percent| {
+                    format!("{percent:+.1}%")
+                }),
+            if delta.baseline_ns.is_none() {
+                "new"
+            } else if delta.regressed(threshold) {
+                "REGRESSED"
+            } else {
+                "ok"
+            }
+            .to_string(),
+        ]);
+    }
+    render(&rows, &[3, 4, 5], false)
+}
+
+/// Fails when any part got slower than
+/// `threshold` percent.
+pub fn check(
+    deltas: &[Delta],
+    threshold: f64,
+) -> miette::Result<()> {
+    let regressed = deltas
+        .iter()
+        .filter(|delta| delta.regressed(threshold))
+        .count();
+    if regressed > 0 {
+        return Err(miette!(
+            "{regressed} part(s) regressed by more than \
+             {threshold}%"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(
+        day: u8,
+        part: u8,
+        median_ns: u64,
+    ) -> Measurement {
+        Measurement {
+            day,
+            part,
+            median_ns,
+            min_ns: median_ns,
+            max_ns: median_ns,
+            samples: 1,
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let times =
+            [5, 1, 3, 9].map(Duration::from_nanos).to_vec();
+        assert_eq!(
+            Measurement {
+                day: 1,
+                part: 1,
+                median_ns: 4,
+                min_ns: 1,
+                max_ns: 9,
+                samples: 4,
+            },
+            Measurement::new(&SOLUTIONS[0], times)
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = [
+            measurement(1, 1, 100),
+            measurement(1, 2, 100),
+        ];
+        let current = [
+            measurement(1, 1, 90),
+            measurement(1, 2, 120),
+            measurement(2, 1, 50),
+        ];
+        let deltas = compare(&baseline, &current);
+
+        assert_eq!(
+            vec![Some(-10.0), Some(20.0), None],
+            deltas
+                .iter()
+                .map(Delta::percent)
+                .collect::<Vec<_>>()
+        );
+        assert!(check(&deltas, 25.0).is_ok());
+        assert!(check(&deltas, 15.0).is_err());
+
+        let table = delta_table(&deltas, 15.0);
+        assert!(table.contains("+20.0% | REGRESSED"));
+        assert!(table.contains("-10.0% | ok"));
+        assert!(table.lines().any(|line| {
+            line.starts_with("02") && line.ends_with("new")
+        }));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "aoc-bench-{}.json",
+            std::process::id()
+        ));
+        let measurements = vec![
+            measurement(3, 2, 42),
+            measurement(4, 1, 7),
+        ];
+        save(&path, &measurements).unwrap();
+        assert_eq!(measurements, load(&path).unwrap());
+    }
+}
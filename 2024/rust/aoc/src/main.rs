@@ -0,0 +1,199 @@
+use aoc::{
+    answers, bench,
+    registry::Solution,
+    runner::{self, DaySelection},
+};
+use clap::Parser;
+use std::{path::PathBuf, time::Duration};
+
+/// Run Advent of Code solutions against their
+/// puzzle inputs and print the answers.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    /// days to run: `all`, a day `7` or an
+    /// inclusive range `3-9`
+    #[clap(default_value = "all")]
+    days: Vec<DaySelection>,
+    /// only run this part
+    #[clap(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// workspace directory holding the `day-XX`
+    /// crates and their inputs
+    #[clap(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/.."))]
+    root: PathBuf,
+    /// compare the answers with the ones recorded
+    /// in each day's `answers.toml` instead of
+    /// printing them
+    #[clap(
+        long,
+        conflicts_with_all = ["bench", "save_baseline", "compare"]
+    )]
+    check: bool,
+    /// time each part several times and print the
+    /// median, min and max instead of the answers
+    #[clap(long)]
+    bench: bool,
+    /// most timed runs per part when benchmarking
+    #[clap(long, default_value_t = 20)]
+    samples: usize,
+    /// seconds after which a part stops being
+    /// sampled, it always runs at least once
+    #[clap(long, default_value_t = 5.0, value_parser = seconds)]
+    max_time: f64,
+    /// also write the measurements as JSON to
+    /// this file
+    #[clap(long, value_name = "PATH")]
+    json: Option<PathBuf>,
+    /// save the measurements as the new baseline,
+    /// implies `--bench`
+    #[clap(long)]
+    save_baseline: bool,
+    /// compare the measurements with the baseline
+    /// and fail on regressions, implies `--bench`
+    #[clap(long)]
+    compare: bool,
+    /// slowdown of a median, in percent, over
+    /// which `--compare` fails
+    #[clap(long, default_value_t = 10.0, value_parser = percent)]
+    threshold: f64,
+    /// baseline file, defaults to
+    /// `<root>/target/aoc-bench/baseline.json`
+    #[clap(long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+}
+
+/// Parses a duration in seconds that
+/// `Duration` can hold.
+fn seconds(text: &str) -> Result<f64, String> {
+    let seconds = text
+        .parse::<f64>()
+        .map_err(|err| err.to_string())?;
+    Duration::try_from_secs_f64(seconds)
+        .map(|_| seconds)
+        .map_err(|_| {
+            format!("`{text}` is not a number of seconds")
+        })
+}
+
+/// Parses a finite, non negative percentage.
+fn percent(text: &str) -> Result<f64, String> {
+    let percent = text
+        .parse::<f64>()
+        .map_err(|err| err.to_string())?;
+    if percent.is_finite() && percent >= 0.0 {
+        Ok(percent)
+    } else {
+        Err(format!("`{text}` is not a percentage"))
+    }
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let solutions = runner::select(&args.days, args.part);
+
+    if args.bench || args.save_baseline || args.compare {
+        return run_bench(&args, solutions);
+    }
+
+    if args.check {
+        let checks = solutions
+            .into_iter()
+            .map(|solution| {
+                answers::check(&args.root, solution)
+            })
+            .collect::<Vec<_>>();
+
+        print!("{}", answers::report(&checks));
+        for check in &checks {
+            if let answers::Status::Failed(err) =
+                &check.status
+            {
+                eprintln!("{err:?}");
+            }
+        }
+        return answers::verify(&checks);
+    }
+
+    let outcomes = solutions
+        .into_iter()
+        .map(|solution| runner::run(&args.root, solution))
+        .collect::<Vec<_>>();
+
+    print!("{}", runner::table(&outcomes));
+    for outcome in &outcomes {
+        if let Err(err) = &outcome.answer {
+            eprintln!("{err:?}");
+        }
+    }
+
+    runner::check(&outcomes)
+}
+
+fn run_bench(
+    args: &Args,
+    solutions: Vec<&'static Solution>,
+) -> miette::Result<()> {
+    let budget = Duration::from_secs_f64(args.max_time);
+    let mut measurements = vec![];
+    let mut failed = 0;
+    for solution in solutions {
+        match bench::measure(
+            &args.root,
+            solution,
+            args.samples,
+            budget,
+        ) {
+            Ok(measurement) => {
+                measurements.push(measurement)
+            }
+            Err(err) => {
+                eprintln!("{err:?}");
+                failed += 1;
+            }
+        }
+    }
+    print!("{}", bench::table(&measurements));
+
+    if let Some(path) = &args.json {
+        bench::save(path, &measurements)?;
+    }
+
+    let baseline_path =
+        args.baseline.clone().unwrap_or_else(|| {
+            args.root.join("target/aoc-bench/baseline.json")
+        });
+    let compared = args
+        .compare
+        .then(|| {
+            let baseline = bench::load(&baseline_path)?;
+            let deltas =
+                bench::compare(&baseline, &measurements);
+            println!();
+            print!(
+                "{}",
+                bench::delta_table(&deltas, args.threshold)
+            );
+            bench::check(&deltas, args.threshold)
+        })
+        .transpose();
+
+    if args.save_baseline {
+        bench::save(&baseline_path, &measurements)?;
+        println!(
+            "saved baseline `{}`",
+            baseline_path.display()
+        );
+    }
+
+    compared?;
+    if failed > 0 {
+        return Err(miette::miette!(
+            "{failed} part(s) could not be measured"
+        ));
+    }
+    Ok(())
+}
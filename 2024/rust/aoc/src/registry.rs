@@ -0,0 +1,99 @@
+/// Signature shared by every day's
+/// `partN::process`.
+pub type Process = fn(&str) -> miette::Result<String>;
+
+/// One runnable puzzle part.
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    pub process: Process,
+}
+
+macro_rules! solutions {
+    ($(
+        $day:literal, $name:literal =>
+            $part1:expr, $part2:expr;
+    )*) => {
+        &[$(
+            Solution {
+                day: $day,
+                part: 1,
+                name: $name,
+                process: $part1,
+            },
+            Solution {
+                day: $day,
+                part: 2,
+                name: $name,
+                process: $part2,
+            },
+        )*]
+    };
+}
+
+/// Every solved part, ordered by day then part.
+pub static SOLUTIONS: &[Solution] = solutions![
+    1, "Historian Hysteria" =>
+        day_01::part1::process, day_01::part2::process;
+    2, "Red-Nosed Reports" =>
+        day_02::part1::process, day_02::part2::process;
+    3, "Mull It Over" =>
+        day_03::part1::process, day_03::part2::process;
+    4, "Ceres Search" =>
+        day_04::part1::process, day_04::part2::process;
+    5, "Print Queue" =>
+        day_05::part1::process, day_05::part2::process;
+    6, "Guard Gallivant" =>
+        day_06::part1::process, day_06::part2::process;
+    7, "Bridge Repair" =>
+        day_07::part1::process, day_07::part2::process;
+    8, "Resonant Collinearity" =>
+        day_08::part1::process, day_08::part2::process;
+    9, "Disk Fragmenter" =>
+        day_09::part1::process, day_09::part2::process;
+    10, "Hoof It" =>
+        day_10::part1::process, day_10::part2::process;
+    11, "Plutonian Pebbles" =>
+        day_11::part1::process, day_11::part2::process;
+    12, "Garden Groups" =>
+        day_12::part1::process, day_12::part2::process;
+    13, "Claw Contraption" =>
+        day_13::part1::process, day_13::part2::process;
+    14, "Restroom Redoubt" =>
+        day_14::part1::process, day_14::part2::process;
+    15, "Warehouse Woes" =>
+        day_15::part1::process, day_15::part2::process;
+    16, "Reindeer Maze" =>
+        day_16::part1::process, day_16::part2::process;
+    17, "Chronospatial Computer" =>
+        day_17::part1::process, day_17::part2::process;
+    18, "RAM Run" =>
+        day_18::part1::process, day_18::part2::process;
+    19, "Linen Layout" =>
+        day_19::part1::process, day_19::part2::process;
+    20, "Race Condition" =>
+        day_20::part1::process, day_20::part2::process;
+    21, "Keypad Conundrum" =>
+        day_21::part1::process, day_21::part2::process;
+    22, "Monkey Market" =>
+        day_22::part1::process, day_22::part2::process;
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solutions_are_ordered_and_complete() {
+        let keys: Vec<_> = SOLUTIONS
+            .iter()
+            .map(|solution| (solution.day, solution.part))
+            .collect();
+        let expected: Vec<_> = (1..=22)
+            .flat_map(|day| [(day, 1), (day, 2)])
+            .collect();
+        assert_eq!(expected, keys);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::registry::{Solution, SOLUTIONS};
+use miette::{miette, WrapErr};
+use std::{
+    fmt::Write,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Days to run, as given on the command line:
+/// `all`, a single day `7` or an inclusive range
+/// `3-9`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySelection(RangeInclusive<u8>);
+
+impl DaySelection {
+    pub fn all() -> Self {
+        DaySelection(1..=25)
+    }
+
+    pub fn contains(&self, day: u8) -> bool {
+        self.0.contains(&day)
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = |value: &str| {
+            value.trim().parse::<u8>().map_err(|_| {
+                format!("invalid day `{value}`")
+            })
+        };
+
+        if s == "all" {
+            return Ok(DaySelection::all());
+        }
+        let s = s.strip_prefix("day-").unwrap_or(s);
+
+        let range = match s.split_once('-') {
+            Some((start, end)) if !start.is_empty() => {
+                day(start)?..=day(end)?
+            }
+            _ => {
+                let day = day(s)?;
+                day..=day
+            }
+        };
+        if range.is_empty() {
+            return Err(format!("empty day range `{s}`"));
+        }
+        Ok(DaySelection(range))
+    }
+}
+
+/// Result of running a single part.
+#[derive(Debug)]
+pub struct Outcome {
+    pub solution: &'static Solution,
+    pub answer: miette::Result<String>,
+    pub elapsed: Duration,
+}
+
+/// Solutions matching any of the selections and
+/// the optional part filter, in registry order.
+pub fn select(
+    days: &[DaySelection],
+    part: Option<u8>,
+) -> Vec<&'static Solution> {
+    SOLUTIONS
+        .iter()
+        .filter(|solution| {
+            days.iter()
+                .any(|days| days.contains(solution.day))
+        })
+        .filter(|solution| {
+            part.is_none_or(|part| part == solution.part)
+        })
+        .collect()
+}
+
+/// Location of a part's input inside the
+/// workspace, `<root>/day-XX/inputN.txt`.
+pub fn input_path(
+    root: &Path,
+    solution: &Solution,
+) -> PathBuf {
+    aoc_input::default_path(
+        root.join(format!("day-{:02}", solution.day)),
+        solution.part,
+    )
+}
+
+/// Reads the part's input and times its
+/// `process`. Reading the input is not part of
+/// the measured time.
+pub fn run(
+    root: &Path,
+    solution: &'static Solution,
+) -> Outcome {
+    let answer =
+        aoc_input::read(input_path(root, solution));
+
+    let start = Instant::now();
+    let answer = answer.and_then(|input| {
+        (solution.process)(&input).wrap_err_with(|| {
+            format!(
+                "process day {:02} part {}",
+                solution.day, solution.part
+            )
+        })
+    });
+
+    Outcome {
+        solution,
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Renders outcomes as an aligned text table with
+/// a total time row.
+pub fn table(outcomes: &[Outcome]) -> String {
+    let header = ["Day", "Part", "Name", "Answer", "Time"]
+        .map(String::from);
+    let mut rows = vec![header];
+    for outcome in outcomes {
+        rows.push([
+            format!("{:02}", outcome.solution.day),
+            outcome.solution.part.to_string(),
+            outcome.solution.name.to_string(),
+            match &outcome.answer {
+                Ok(answer) => answer.clone(),
+                Err(_) => "ERROR".to_string(),
+            },
+            format!("{:.2?}", outcome.elapsed),
+        ]);
+    }
+    let total: Duration = outcomes
+        .iter()
+        .map(|outcome| outcome.elapsed)
+        .sum();
+    rows.push([
+        String::new(),
+        String::new(),
+        "Total".to_string(),
+        String::new(),
+        format!("{total:.2?}"),
+    ]);
+
+    render(&rows, &[4], true)
+}
+
+/// Aligns rows of cells into a text table with a
+/// rule under the header row and, with `footer`,
+/// another one above the last row. Columns listed
+/// in `right` are right aligned.
+pub(crate) fn render<const N: usize>(
+    rows: &[[String; N]],
+    right: &[usize],
+    footer: bool,
+) -> String {
+    let widths = (0..N)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut output = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if right.contains(&column) {
+                    format!("{cell:>width$}")
+                } else {
+                    format!("{cell:<width$}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        writeln!(output, "{}", line.trim_end()).unwrap();
+        if index == 0 || (footer && index + 2 == rows.len())
+        {
+            let rule = widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("-+-");
+            writeln!(output, "{rule}").unwrap();
+        }
+    }
+    output
+}
+
+/// Fails with a summary when any outcome is an
+/// error.
+pub fn check(outcomes: &[Outcome]) -> miette::Result<()> {
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.answer.is_err())
+        .count();
+    if failed > 0 {
+        return Err(miette!(
+            "{failed} of {} part(s) failed",
+            outcomes.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("all", 1..=25)]
+    #[case("7", 7..=7)]
+    #[case("day-07", 7..=7)]
+    #[case("3-9", 3..=9)]
+    fn test_day_selection(
+        #[case] input: &str,
+        #[case] expected: RangeInclusive<u8>,
+    ) {
+        assert_eq!(
+            Ok(DaySelection(expected)),
+            input.parse()
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("nine")]
+    #[case("9-3")]
+    #[case("-3")]
+    fn test_day_selection_invalid(#[case] input: &str) {
+        assert!(input.parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let selected = select(
+            &[
+                "2".parse().unwrap(),
+                "20-21".parse().unwrap(),
+            ],
+            Some(2),
+        )
+        .iter()
+        .map(|solution| (solution.day, solution.part))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(2, 2), (20, 2), (21, 2)],
+            selected
+        );
+    }
+
+    #[test]
+    fn test_missing_input_is_an_error() {
+        let outcomes = vec![run(
+            Path::new("/nonexistent"),
+            &SOLUTIONS[0],
+        )];
+        assert!(outcomes[0].answer.is_err());
+        assert!(check(&outcomes).is_err());
+        assert!(table(&outcomes).contains("ERROR"));
+    }
+}
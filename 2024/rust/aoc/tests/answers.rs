@@ -0,0 +1,19 @@
+//! Runs every part against its downloaded input
+//! and compares with the answers recorded in the
+//! day's `answers.toml`. Days without either are
+//! skipped, so a fresh clone passes.
+use aoc::{answers, registry::SOLUTIONS};
+use std::path::Path;
+
+#[test]
+fn recorded_answers_still_match() -> miette::Result<()> {
+    let root =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let checks = SOLUTIONS
+        .iter()
+        .map(|solution| answers::check(&root, solution))
+        .collect::<Vec<_>>();
+
+    print!("{}", answers::report(&checks));
+    answers::verify(&checks)
+}
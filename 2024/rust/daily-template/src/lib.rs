@@ -1,2 +1,3 @@
+mod parser;
 pub mod part1;
 pub mod part2;
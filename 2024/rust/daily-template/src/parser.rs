@@ -0,0 +1,112 @@
+//! Parsing of the puzzle input, shared by both
+//! parts.
+{% if parser == "grid" -%}
+use aoc_grid::{parser::char_grid, Grid};
+use aoc_parse::IResult;
+use nom::{
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    sequence::terminated,
+};
+
+/// The map, keeping every character as is.
+pub fn parse(input: &str) -> IResult<Grid<char>> {
+    all_consuming(terminated(char_grid, opt(line_ending)))(
+        input,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::IVec2;
+
+    #[test]
+    fn test_parse() {
+        let (_, grid) = parse("#.\n.#\n").unwrap();
+        assert_eq!(IVec2::new(2, 2), grid.size());
+    }
+}
+{%- elsif parser == "sections" -%}
+use aoc_parse::IResult;
+use nom::{
+    bytes::complete::take_till1,
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    error::context,
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated},
+};
+
+/// The lines of the two sections separated by a
+/// blank line.
+pub fn parse(
+    input: &str,
+) -> IResult<(Vec<&str>, Vec<&str>)> {
+    all_consuming(terminated(
+        separated_pair(
+            context("first section", section),
+            pair(line_ending, line_ending),
+            context("second section", section),
+        ),
+        opt(line_ending),
+    ))(input)
+}
+
+fn section(input: &str) -> IResult<Vec<&str>> {
+    separated_list1(
+        line_ending,
+        take_till1(|ch| ch == '\r' || ch == '\n'),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Ok(("", (vec!["a", "b"], vec!["c"]))),
+            parse("a\nb\n\nc\n")
+        );
+    }
+}
+{%- else -%}
+use aoc_parse::IResult;
+use nom::{
+    character::complete::{self, line_ending, space1},
+    combinator::{all_consuming, opt},
+    error::context,
+    multi::separated_list1,
+    sequence::terminated,
+};
+
+/// The numbers of every line.
+pub fn parse(input: &str) -> IResult<Vec<Vec<u32>>> {
+    all_consuming(terminated(
+        separated_list1(line_ending, line),
+        opt(line_ending),
+    ))(input)
+}
+
+fn line(input: &str) -> IResult<Vec<u32>> {
+    context(
+        "line",
+        separated_list1(space1, complete::u32),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Ok(("", vec![vec![1, 2], vec![3]])),
+            parse("1 2\n3\n")
+        );
+    }
+}
+{%- endif %}
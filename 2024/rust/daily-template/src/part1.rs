@@ -1,13 +1,12 @@
+use crate::parser::parse;
+use aoc_parse::ParseError;
+
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let result = parse(input)
-        .map_err(|err| miette::miette!("Parse error: {}", err))?;
-
-    Ok(result.to_string())
-}
+    let (_, _puzzle) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
-fn parse(input: &str) -> Result<String, &'static str> {
-    Err("{{crate_name}} - part 1")
+    Err(miette::miette!("{{crate_name}} - part 1"))
 }
 
 #[cfg(test)]
@@ -15,16 +14,19 @@ mod tests {
     use super::*;
     use rstest::rstest;
 
+    /// `example1.txt` from `just get-examples`
+    /// when present, the inline input
+    /// otherwise.
     #[rstest]
     #[case(
-        "",
+        aoc_input::example(env!("CARGO_MANIFEST_DIR"), 1, ""),
         ""
     )]
     fn test_process(
-        #[case] input: &str,
+        #[case] input: String,
         #[case] expected: &str,
     ) -> miette::Result<()> {
-        assert_eq!(expected, process(input)?);
+        assert_eq!(expected, process(&input)?);
         Ok(())
     }
 }
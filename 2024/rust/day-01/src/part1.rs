@@ -1,13 +1,12 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     character::complete,
     combinator::{all_consuming, opt, value},
     multi::separated_list1,
     sequence::separated_pair,
-    IResult,
 };
 
-fn parse(input: &str) -> IResult<&str, Vec<(i32, i32)>> {
+fn parse(input: &str) -> IResult<Vec<(i32, i32)>> {
     let (rest, output) = separated_list1(
         complete::line_ending,
         separated_pair(
@@ -26,7 +25,7 @@ fn parse(input: &str) -> IResult<&str, Vec<(i32, i32)>> {
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, pairs) = parse(input)
-        .map_err(|e| miette!("parse failed {}", e))?;
+        .map_err(|e| ParseError::new(input, e))?;
 
     let (mut left, mut right): (Vec<_>, Vec<_>) =
         pairs.into_iter().unzip();
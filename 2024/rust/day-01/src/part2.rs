@@ -1,14 +1,13 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     character::complete,
     combinator::{all_consuming, opt, value},
     multi::separated_list1,
     sequence::separated_pair,
-    IResult,
 };
 use std::collections::HashMap;
 
-fn parse(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
+fn parse(input: &str) -> IResult<Vec<(u32, u32)>> {
     let (rest, output) = separated_list1(
         complete::line_ending,
         separated_pair(
@@ -27,7 +26,7 @@ fn parse(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, pairs) = parse(input)
-        .map_err(|e| miette!("parse failed {}", e))?;
+        .map_err(|e| ParseError::new(input, e))?;
 
     let (left, right) = pairs.into_iter().fold(
         (HashMap::new(), HashMap::new()),
@@ -1,6 +1,6 @@
 use crate::part1::Direction::{Decreasing, Increasing};
+use aoc_parse::{IResult, ParseError};
 use itertools::Itertools;
-use miette::miette;
 use nom::{
     character::{
         complete,
@@ -8,7 +8,6 @@ use nom::{
     },
     combinator::{all_consuming, opt, value},
     multi::separated_list1,
-    IResult,
 };
 
 #[derive(Debug, Copy, Clone)]
@@ -53,7 +52,7 @@ impl Report {
     }
 }
 
-fn report(input: &str) -> IResult<&str, Report> {
+fn report(input: &str) -> IResult<Report> {
     let (remaining, levels) =
         separated_list1(space1, complete::u32)(input)?;
 
@@ -62,7 +61,7 @@ fn report(input: &str) -> IResult<&str, Report> {
     Ok((remaining, report))
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Report>> {
+fn parse(input: &str) -> IResult<Vec<Report>> {
     let (remaining, reports) =
         separated_list1(line_ending, report)(input)?;
 
@@ -74,7 +73,7 @@ fn parse(input: &str) -> IResult<&str, Vec<Report>> {
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, reports) = parse(input)
-        .map_err(|e| miette!("Parsing error: {}", e))?;
+        .map_err(|e| ParseError::new(input, e))?;
 
     let count =
         reports.iter().filter(|r| r.is_safe()).count();
@@ -98,9 +97,7 @@ mod tests {
         #[case] is_safe: bool,
     ) -> miette::Result<()> {
         let (_, report) = all_consuming(report)(input)
-            .map_err(|e| {
-                miette!("Report parsing error: {}", e)
-            })?;
+            .map_err(|e| ParseError::new(input, e))?;
 
         assert_eq!(report.is_safe(), is_safe);
         Ok(())
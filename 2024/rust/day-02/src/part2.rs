@@ -1,5 +1,5 @@
+use aoc_parse::{IResult, ParseError};
 use itertools::{chain, Itertools};
-use miette::miette;
 use nom::{
     character::{
         complete,
@@ -7,7 +7,6 @@ use nom::{
     },
     combinator::{all_consuming, opt, value},
     multi::separated_list1,
-    IResult,
 };
 
 #[derive(Debug, Clone)]
@@ -64,7 +63,7 @@ impl Report {
     }
 }
 
-fn report(input: &str) -> IResult<&str, Report> {
+fn report(input: &str) -> IResult<Report> {
     let (remaining, levels) =
         separated_list1(space1, complete::u32)(input)?;
 
@@ -73,7 +72,7 @@ fn report(input: &str) -> IResult<&str, Report> {
     Ok((remaining, report))
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Report>> {
+fn parse(input: &str) -> IResult<Vec<Report>> {
     let (remaining, reports) =
         separated_list1(line_ending, report)(input)?;
 
@@ -85,7 +84,7 @@ fn parse(input: &str) -> IResult<&str, Vec<Report>> {
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, reports) = parse(input)
-        .map_err(|e| miette!("Parsing error: {}", e))?;
+        .map_err(|e| ParseError::new(input, e))?;
 
     let count = reports
         .iter()
@@ -111,9 +110,7 @@ mod tests {
         #[case] is_safe: bool,
     ) -> miette::Result<()> {
         let (_, report) = all_consuming(report)(input)
-            .map_err(|e| {
-                miette!("Report parsing error: {}", e)
-            })?;
+            .map_err(|e| ParseError::new(input, e))?;
 
         assert_eq!(report.allow_one_error(), is_safe);
         Ok(())
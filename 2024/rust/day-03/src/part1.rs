@@ -1,16 +1,16 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::complete,
     multi::{many1, many_till},
     sequence::{delimited, separated_pair},
-    IResult, Parser,
+    Parser,
 };
 
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    let (_, instructions) = parse(_input)
-        .map_err(|err| miette!("Parsing error: {}", err))?;
+pub fn process(input: &str) -> miette::Result<String> {
+    let (_, instructions) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let sum = instructions
         .into_iter()
@@ -22,14 +22,14 @@ pub fn process(_input: &str) -> miette::Result<String> {
     Ok(sum.to_string())
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Instruction>> {
+fn parse(input: &str) -> IResult<Vec<Instruction>> {
     many1(
         many_till(complete::anychar, instruction)
             .map(|(_, instruction)| instruction),
     )(input)
 }
 
-fn instruction(input: &str) -> IResult<&str, Instruction> {
+fn instruction(input: &str) -> IResult<Instruction> {
     let (input, _operation) = tag("mul")(input)?;
 
     let (input, pair) = delimited(
@@ -1,4 +1,4 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -6,13 +6,13 @@ use nom::{
     combinator::value,
     multi::{many1, many_till},
     sequence::{delimited, separated_pair},
-    IResult, Parser,
+    Parser,
 };
 
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    let (_, instructions) = parse(_input)
-        .map_err(|err| miette!("Parsing error: {}", err))?;
+pub fn process(input: &str) -> miette::Result<String> {
+    let (_, instructions) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let mut current = Instruction::Do;
 
@@ -36,14 +36,14 @@ pub fn process(_input: &str) -> miette::Result<String> {
     Ok(sum.to_string())
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Instruction>> {
+fn parse(input: &str) -> IResult<Vec<Instruction>> {
     many1(
         many_till(complete::anychar, instruction)
             .map(|(_, instruction)| instruction),
     )(input)
 }
 
-fn instruction(input: &str) -> IResult<&str, Instruction> {
+fn instruction(input: &str) -> IResult<Instruction> {
     alt((
         multiply,
         value(Instruction::Do, tag("do()")),
@@ -51,7 +51,7 @@ fn instruction(input: &str) -> IResult<&str, Instruction> {
     ))(input)
 }
 
-fn multiply(input: &str) -> IResult<&str, Instruction> {
+fn multiply(input: &str) -> IResult<Instruction> {
     let (input, _operation) = tag("mul")(input)?;
 
     let (input, pair) = delimited(
@@ -1,5 +1,6 @@
+use aoc_grid::Grid;
+use aoc_parse::ParseError;
 use glam::IVec2;
-use std::collections::HashMap;
 
 const DIRECTIONS: [IVec2; 8] = [
     IVec2::new(1, 0),
@@ -14,31 +15,25 @@ const DIRECTIONS: [IVec2; 8] = [
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, ch)| {
-                (IVec2::new(x as i32, y as i32), ch)
-            })
-        })
-        .collect::<HashMap<_, _>>();
+    let grid: Grid<char> = input
+        .parse()
+        .map_err(|err| ParseError::new(input, err))?;
 
     let mas = ['M', 'A', 'S'];
 
     let sum = grid
-        .iter()
+        .iter_positions()
         .filter(|&(_position, char)| *char == 'X')
         .fold(0, |acc, (position, _char)| {
             acc + DIRECTIONS
                 .iter()
                 .filter(|&direction| {
-                    let mut position = *position;
+                    let mut position = position;
 
                     mas.iter().all(|letter| {
                         position += direction;
                         if let Some(char) =
-                            grid.get(&position)
+                            grid.get(position)
                         {
                             return *char == *letter;
                         }
@@ -1,5 +1,7 @@
+use aoc_grid::Grid;
+use aoc_parse::ParseError;
 use glam::IVec2;
-use std::{collections::HashMap, iter::zip};
+use std::iter::zip;
 
 const DIRECTIONS_SLASH: [IVec2; 2] =
     [IVec2::new(1, -1), IVec2::new(-1, 1)];
@@ -9,23 +11,17 @@ const MAS: [char; 2] = ['M', 'S'];
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, ch)| {
-                (IVec2::new(x as i32, y as i32), ch)
-            })
-        })
-        .collect::<HashMap<_, _>>();
+    let grid: Grid<char> = input
+        .parse()
+        .map_err(|err| ParseError::new(input, err))?;
 
     let sum = grid
-        .iter()
+        .iter_positions()
         .filter(|&(_position, char)| *char == 'A')
         .filter(|&(position, _char)| {
             let check =
                 |(direction, mas): (&IVec2, &char)| {
-                    grid.get(&(position + direction))
+                    grid.get(position + *direction)
                         .is_some_and(|actual| {
                             *actual == *mas
                         })
@@ -1,18 +1,17 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
     combinator::{all_consuming, opt},
     multi::{many1, separated_list1},
     sequence::{preceded, separated_pair, terminated},
-    IResult,
 };
 use std::{collections::HashMap, ops::Not};
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, (rules, updates)) = parse(input)
-        .map_err(|err| miette!("Parse error: {}", err))?;
+        .map_err(|err| ParseError::new(input, err))?;
 
     let precedence = rules.iter().fold(
         HashMap::new(),
@@ -43,7 +42,7 @@ struct Update(Vec<u32>);
 
 impl Update {
     fn is_valid(
-        self: &Self,
+        &self,
         precedence: &HashMap<u32, Vec<u32>>,
     ) -> bool {
         let mut checked = Vec::with_capacity(self.0.len());
@@ -63,9 +62,7 @@ impl Update {
     }
 }
 
-fn parse(
-    input: &str,
-) -> IResult<&str, (Vec<Rule>, Vec<Update>)> {
+fn parse(input: &str) -> IResult<(Vec<Rule>, Vec<Update>)> {
     let (input, rules) = many1(rule)(input)?;
     let (input, updates) = all_consuming(preceded(
         line_ending,
@@ -75,7 +72,7 @@ fn parse(
     Ok((input, (rules, updates)))
 }
 
-fn rule(input: &str) -> IResult<&str, Rule> {
+fn rule(input: &str) -> IResult<Rule> {
     terminated(
         separated_pair(
             complete::u32,
@@ -86,7 +83,7 @@ fn rule(input: &str) -> IResult<&str, Rule> {
     )(input)
 }
 
-fn update(input: &str) -> IResult<&str, Update> {
+fn update(input: &str) -> IResult<Update> {
     let (input, update) = terminated(
         separated_list1(tag(","), complete::u32),
         opt(line_ending),
@@ -1,18 +1,17 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
     combinator::{all_consuming, opt},
     multi::{many1, separated_list1},
     sequence::{preceded, separated_pair, terminated},
-    IResult,
 };
 use std::{collections::HashMap, ops::Not};
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, (rules, updates)) = parse(input)
-        .map_err(|err| miette!("Parse error: {}", err))?;
+        .map_err(|err| ParseError::new(input, err))?;
 
     let rules: Rules = rules.iter().fold(
         HashMap::new(),
@@ -45,7 +44,7 @@ type Rules = HashMap<u32, Vec<u32>>;
 struct Update(Vec<u32>);
 
 impl Update {
-    fn is_valid(self: &Self, rules: &Rules) -> bool {
+    fn is_valid(&self, rules: &Rules) -> bool {
         let mut checked = Vec::with_capacity(self.0.len());
         self.0.iter().all(|x| {
             let mut valid = true;
@@ -62,7 +61,7 @@ impl Update {
         })
     }
 
-    fn fix_ordering(self: &Self, rules: &Rules) -> Self {
+    fn fix_ordering(&self, rules: &Rules) -> Self {
         if self.is_valid(rules) {
             return Update(self.0.clone());
         }
@@ -89,9 +88,7 @@ impl Update {
     }
 }
 
-fn parse(
-    input: &str,
-) -> IResult<&str, (Vec<Rule>, Vec<Update>)> {
+fn parse(input: &str) -> IResult<(Vec<Rule>, Vec<Update>)> {
     let (input, rules) = many1(rule)(input)?;
     let (input, updates) = all_consuming(preceded(
         line_ending,
@@ -101,7 +98,7 @@ fn parse(
     Ok((input, (rules, updates)))
 }
 
-fn rule(input: &str) -> IResult<&str, Rule> {
+fn rule(input: &str) -> IResult<Rule> {
     terminated(
         separated_pair(
             complete::u32,
@@ -112,7 +109,7 @@ fn rule(input: &str) -> IResult<&str, Rule> {
     )(input)
 }
 
-fn update(input: &str) -> IResult<&str, Update> {
+fn update(input: &str) -> IResult<Update> {
     let (input, update) = terminated(
         separated_list1(tag(","), complete::u32),
         opt(line_ending),
@@ -1,21 +1,13 @@
+use aoc_grid::parser::grid;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
-use miette::miette;
-use nom::{
-    character::complete::{line_ending, one_of},
-    multi::{many1, separated_list1},
-    IResult,
-};
-use nom_locate::LocatedSpan;
 use std::collections::HashSet;
 
-type Span<'a> = LocatedSpan<&'a str>;
-
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, (map_size, objects, position)) =
-        parse(Span::new(input)).map_err(|err| {
-            miette!("Parse error: {}", err)
-        })?;
+        parse(input)
+            .map_err(|err| ParseError::new(input, err))?;
 
     let mut position =
         position.expect("position should be defined");
@@ -38,54 +30,18 @@ pub fn process(input: &str) -> miette::Result<String> {
 }
 
 fn parse(
-    input: Span,
-) -> IResult<Span, (IVec2, Vec<IVec2>, Option<IVec2>)> {
-    let (input, objects) =
-        separated_list1(line_ending, many1(token))(input)?;
-
-    let size = objects
-        .iter()
-        .flatten()
-        .last()
-        .and_then(|&(last, _)| Some(last))
-        .unwrap_or_default();
+    input: &str,
+) -> IResult<(IVec2, Vec<IVec2>, Option<IVec2>)> {
+    let (input, map) =
+        grid(|ch| ".^#".contains(ch).then_some(ch))(input)?;
 
-    let walls = objects
-        .iter()
-        .flatten()
-        .filter_map(|&(position, tag)| {
-            if tag == '#' {
-                Some(position)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let position = objects.iter().flatten().find_map(
-        |&(position, tag)| {
-            if tag == '^' {
-                Some(position)
-            } else {
-                None
-            }
-        },
-    );
+    let size = map.size() - IVec2::ONE;
+    let walls = map.find_all('#').collect();
+    let position = map.find('^');
 
     Ok((input, (size, walls, position)))
 }
 
-fn token(input: Span) -> IResult<Span, (IVec2, char)> {
-    let location = IVec2::new(
-        input.get_column() as i32 - 1,
-        input.location_line() as i32 - 1,
-    );
-
-    let (input, token) = one_of(".^#")(input)?;
-
-    Ok((input, (location, token)))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,18 +1,17 @@
+use aoc_parse::{IResult, ParseError};
 use itertools::Itertools;
-use miette::miette;
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
     combinator::{all_consuming, opt},
     multi::{many1, separated_list1},
     sequence::{separated_pair, terminated},
-    IResult,
 };
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, operations) = parse(input)
-        .map_err(|err| miette!("Parse error: {}", err))?;
+        .map_err(|err| ParseError::new(input, err))?;
 
     let sum: u64 = operations
         .iter()
@@ -28,15 +27,11 @@ pub fn process(input: &str) -> miette::Result<String> {
     Ok(sum.to_string())
 }
 
-fn parse(
-    input: &str,
-) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
+fn parse(input: &str) -> IResult<Vec<(u64, Vec<u64>)>> {
     all_consuming(many1(operation))(input)
 }
 
-fn operation(
-    input: &str,
-) -> IResult<&str, (u64, Vec<u64>)> {
+fn operation(input: &str) -> IResult<(u64, Vec<u64>)> {
     terminated(
         separated_pair(
             complete::u64,
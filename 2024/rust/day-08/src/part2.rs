@@ -1,44 +1,30 @@
+use aoc_grid::{in_bounds, Grid};
+use aoc_parse::ParseError;
 use glam::IVec2;
 use itertools::{chain, Itertools};
 use std::{collections::HashMap, iter::successors};
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let mut grid_size = IVec2::default();
+    let grid: Grid<char> = input
+        .parse()
+        .map_err(|err| ParseError::new(input, err))?;
+    let grid_size = grid.size();
 
-    let antennas = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            grid_size.y += 1;
-            grid_size.x = line.len() as i32;
-
-            line.chars().enumerate().filter_map(
-                move |(x, ch)| {
-                    if ch != '.' {
-                        Some((
-                            ch,
-                            IVec2::new(x as i32, y as i32),
-                        ))
-                    } else {
-                        None
-                    }
-                },
-            )
-        })
+    let antennas = grid
+        .iter_positions()
+        .filter(|(_position, ch)| **ch != '.')
         .fold(
             HashMap::new(),
             |mut acc: HashMap<_, Vec<_>>,
-             (ch, position)| {
-                acc.entry(ch).or_default().push(position);
+             (position, ch)| {
+                acc.entry(*ch).or_default().push(position);
                 acc
             },
         );
 
-    let in_bound_check = |position: &IVec2| {
-        position.cmpge(IVec2::ZERO).all()
-            && position.cmplt(grid_size).all()
-    };
+    let in_bound_check =
+        |position: &IVec2| in_bounds(*position, grid_size);
 
     let antinodes: usize = antennas
         .iter()
@@ -1,26 +1,24 @@
+use aoc_grid::parser::grid;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
-use miette::miette;
 use nom::{
-    character::complete::{anychar, line_ending},
-    combinator::{all_consuming, map_opt},
-    multi::{many0, separated_list0},
-    IResult,
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    sequence::terminated,
 };
-use nom_locate::{position, LocatedSpan};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-type Span<'a> = LocatedSpan<&'a str>;
-type Grid = HashMap<IVec2, u32>;
+type Grid = aoc_grid::Grid<u32>;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, grid) = parse(Span::new(input))
-        .map_err(|err| miette!("Parse error: {}", err))?;
+    let (_, grid) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let count: usize = grid
-        .iter()
+        .iter_positions()
         .filter(|&(_pos, height)| *height == 0)
-        .map(|(pos, _height)| trailhead_score(&grid, pos))
+        .map(|(pos, _height)| trailhead_score(&grid, &pos))
         .sum();
 
     Ok(count.to_string())
@@ -35,7 +33,7 @@ fn trailhead_score(grid: &Grid, start: &IVec2) -> usize {
             .iter()
             .flat_map(|current_pos| {
                 let current_height = grid
-                    .get(current_pos)
+                    .get(*current_pos)
                     .unwrap_or(&u32::MAX);
                 visited.insert(*current_pos);
 
@@ -49,7 +47,7 @@ fn trailhead_score(grid: &Grid, start: &IVec2) -> usize {
                 .map(|direction| *current_pos + *direction)
                 .filter(|next_step| {
                     !to_visit.contains(next_step)
-                        && grid.get(next_step).is_some_and(
+                        && grid.get(*next_step).is_some_and(
                             |height| {
                                 *height
                                     == *current_height + 1
@@ -63,35 +61,17 @@ fn trailhead_score(grid: &Grid, start: &IVec2) -> usize {
     visited
         .iter()
         .filter(|pos| {
-            grid.get(*pos)
+            grid.get(**pos)
                 .is_some_and(|height| *height == 9)
         })
         .count()
 }
 
-fn parse(input: Span) -> IResult<Span, Grid> {
-    let (input, lines) = all_consuming(separated_list0(
-        line_ending,
-        many0(pos_height),
-    ))(input)?;
-
-    let map =
-        lines.iter().flatten().copied().collect::<Grid>();
-
-    Ok((input, map))
-}
-
-fn pos_height(input: Span) -> IResult<Span, (IVec2, u32)> {
-    let (input, position) = position(input)?;
-    let line = position.location_line() as i32 - 1;
-    let column = position.get_column() as i32 - 1;
-    let (input, height) =
-        map_opt(anychar, |c| c.to_digit(10))(input)?;
-
-    Ok((
-        input,
-        (IVec2::new(column, line), height),
-    ))
+fn parse(input: &str) -> IResult<Grid> {
+    all_consuming(terminated(
+        grid(|c| c.to_digit(10)),
+        opt(line_ending),
+    ))(input)
 }
 
 #[cfg(test)]
@@ -1,34 +1,29 @@
+use aoc_grid::parser::grid;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
-use miette::miette;
 use nom::{
-    character::complete::{anychar, line_ending},
-    combinator::{all_consuming, map_opt},
-    multi::{many0, separated_list0},
-    IResult,
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    sequence::terminated,
 };
-use nom_locate::{position, LocatedSpan};
 use pathfinding::prelude::count_paths;
-use std::{
-    collections::{HashMap, HashSet},
-    iter::repeat,
-};
+use std::{collections::HashSet, iter::repeat};
 
-type Span<'a> = LocatedSpan<&'a str>;
-type Grid = HashMap<IVec2, u32>;
+type Grid = aoc_grid::Grid<u32>;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, grid) = parse(Span::new(input))
-        .map_err(|err| miette!("Parse error: {}", err))?;
+    let (_, grid) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let count: usize = grid
-        .iter()
+        .iter_positions()
         .filter(|&(_pos, height)| *height == 0)
         .map(|(pos, _height)| {
-            (pos, trailhead_search(&grid, pos))
+            (pos, trailhead_search(&grid, &pos))
         })
         .flat_map(|(start, ends)| {
-            repeat(*start).zip(ends.into_iter())
+            repeat(start).zip(ends.into_iter())
         })
         .map(|(start, end)| {
             trailhead_rate(&grid, &start, &end)
@@ -71,7 +66,8 @@ fn trailhead_search(
     visited
         .into_iter()
         .filter(|pos| {
-            grid.get(pos).is_some_and(|height| *height == 9)
+            grid.get(*pos)
+                .is_some_and(|height| *height == 9)
         })
         .collect()
 }
@@ -81,42 +77,23 @@ fn trail_successors<'a>(
     pos: &IVec2,
 ) -> impl Iterator<Item = IVec2> + use<'a> {
     let pos = *pos;
-    let current_height =
-        grid.get(&pos).unwrap_or(&u32::MAX);
+    let current_height = grid.get(pos).unwrap_or(&u32::MAX);
 
     [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y]
         .into_iter()
         .map(move |direction| pos + direction)
         .filter(|next_step| {
-            grid.get(next_step).is_some_and(|height| {
+            grid.get(*next_step).is_some_and(|height| {
                 *height == *current_height + 1
             })
         })
 }
 
-fn parse(input: Span) -> IResult<Span, Grid> {
-    let (input, lines) = all_consuming(separated_list0(
-        line_ending,
-        many0(pos_height),
-    ))(input)?;
-
-    let map =
-        lines.iter().flatten().copied().collect::<Grid>();
-
-    Ok((input, map))
-}
-
-fn pos_height(input: Span) -> IResult<Span, (IVec2, u32)> {
-    let (input, position) = position(input)?;
-    let line = position.location_line() as i32 - 1;
-    let column = position.get_column() as i32 - 1;
-    let (input, height) =
-        map_opt(anychar, |c| c.to_digit(10))(input)?;
-
-    Ok((
-        input,
-        (IVec2::new(column, line), height),
-    ))
+fn parse(input: &str) -> IResult<Grid> {
+    all_consuming(terminated(
+        grid(|c| c.to_digit(10)),
+        opt(line_ending),
+    ))(input)
 }
 
 #[cfg(test)]
@@ -6,18 +6,31 @@ fn main() {
 }
 
 #[divan::bench]
-fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+fn part1(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        1,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part1::process(divan::black_box(&input)).unwrap()
+    });
 }
 
 #[divan::bench]
-fn part2() {
-    part2::process(
-        divan::black_box(include_str!("../input2.txt",)),
-        25,
-    )
-    .unwrap();
+fn part2(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        2,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part2::process_with(
+            divan::black_box(&input),
+            &Params { blinks: 25 },
+        )
+        .unwrap()
+    });
 }
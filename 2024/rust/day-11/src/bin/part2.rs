@@ -1,13 +1,16 @@
-use day_11::part2::process;
+use day_11::{part2::process_with, Params};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
-    let file = include_str!("../../input2.txt");
-    let result =
-        process(file, 75).context("process part 2")?;
+    let (file, params) = aoc_input::load_with::<Params>(
+        env!("CARGO_MANIFEST_DIR"),
+        2,
+    )?;
+    let result = process_with(&file, &params)
+        .context("process part 2")?;
     println!("{}", result);
     Ok(())
 }
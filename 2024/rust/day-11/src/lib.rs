@@ -1,2 +1,17 @@
 pub mod part1;
 pub mod part2;
+
+/// Parameters of part 2, the defaults are the
+/// real puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// number of times the stones blink
+    #[clap(long, default_value_t = Params::default().blinks)]
+    pub blinks: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { blinks: 75 }
+    }
+}
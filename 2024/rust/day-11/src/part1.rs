@@ -1,17 +1,16 @@
-use miette::miette;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
     combinator::{all_consuming, opt},
     multi::separated_list1,
     sequence::terminated,
-    IResult,
 };
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, rocks) = parse(input)
-        .map_err(|err| miette!("Parse err: {}", err))?;
+        .map_err(|err| ParseError::new(input, err))?;
 
     let mut rocks: Box<dyn Iterator<Item = u64>> =
         Box::new(rocks.iter().copied());
@@ -43,7 +42,7 @@ fn blink(
     })
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<u64>> {
+fn parse(input: &str) -> IResult<Vec<u64>> {
     all_consuming(terminated(
         separated_list1(tag(" "), complete::u64),
         opt(line_ending),
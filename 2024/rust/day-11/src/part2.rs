@@ -1,21 +1,26 @@
-use miette::miette;
+use crate::Params;
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
     combinator::{all_consuming, opt},
     multi::separated_list1,
     sequence::terminated,
-    IResult,
 };
 use std::{collections::HashMap, iter};
 
 #[tracing::instrument(skip(input))]
-pub fn process(
+pub fn process(input: &str) -> miette::Result<String> {
+    process_with(input, &Params::default())
+}
+
+#[tracing::instrument(skip(input))]
+pub fn process_with(
     input: &str,
-    blinks: u32,
+    params: &Params,
 ) -> miette::Result<String> {
     let (_, rocks) = parse(input)
-        .map_err(|err| miette!("Parse err: {}", err))?;
+        .map_err(|err| ParseError::new(input, err))?;
 
     let mut cache = HashMap::new();
     for rock in rocks {
@@ -26,7 +31,7 @@ pub fn process(
     }
 
     let mut rocks = cache;
-    for _ in 0..blinks {
+    for _ in 0..params.blinks {
         rocks = blink_count(rocks);
     }
 
@@ -53,12 +58,8 @@ fn blink_count(
                 (number, _) => vec![number * 2024],
             };
 
-            rocks
-                .into_iter()
-                .zip(iter::repeat(*count))
-                .into_iter()
+            rocks.into_iter().zip(iter::repeat(*count))
         })
-        .into_iter()
         .collect();
 
     let mut new_counts: HashMap<u64, u64> = HashMap::new();
@@ -72,7 +73,7 @@ fn blink_count(
     new_counts
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<u64>> {
+fn parse(input: &str) -> IResult<Vec<u64>> {
     all_consuming(terminated(
         separated_list1(tag(" "), complete::u64),
         opt(line_ending),
@@ -92,7 +93,10 @@ mod tests {
         #[case] blinks: u32,
         #[case] expected: &str,
     ) -> miette::Result<()> {
-        assert_eq!(expected, process(input, blinks)?);
+        assert_eq!(
+            expected,
+            process_with(input, &Params { blinks })?
+        );
         Ok(())
     }
 }
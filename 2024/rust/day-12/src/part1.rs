@@ -1,4 +1,5 @@
-use miette::miette;
+use aoc_grid::Grid;
+use aoc_parse::ParseError;
 use petgraph::{
     adj::NodeIndex, algo::condensation,
     graphmap::UnGraphMap, visit::IntoNodeReferences,
@@ -10,8 +11,7 @@ const DIRECTIONS: [(i32, i32); 4] =
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let grid = parse(input)
-        .map_err(|err| miette!("Parse error: {}", err))?;
+    let grid = parse(input)?;
 
     let mut graph = UnGraphMap::new();
 
@@ -56,17 +56,14 @@ pub fn process(input: &str) -> miette::Result<String> {
 fn parse(
     input: &str,
 ) -> miette::Result<HashMap<(i32, i32), char>> {
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, ch)| {
-                ((x as i32, y as i32), ch)
-            })
-        })
-        .collect::<HashMap<_, _>>();
+    let grid: Grid<char> = input
+        .parse()
+        .map_err(|err| ParseError::new(input, err))?;
 
-    Ok(grid)
+    Ok(grid
+        .iter_positions()
+        .map(|(pos, ch)| ((pos.x, pos.y), *ch))
+        .collect())
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
+use aoc_grid::Grid;
+use aoc_parse::ParseError;
 use itertools::Itertools;
-use miette::miette;
 use petgraph::{
     adj::NodeIndex, algo::condensation,
     graphmap::UnGraphMap, visit::IntoNodeReferences,
@@ -13,8 +14,7 @@ const DIRECTIONS_CW: [(i32, i32); 4] =
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let grid = parse(input)
-        .map_err(|err| miette!("Parse error: {}", err))?;
+    let grid = parse(input)?;
 
     let mut graph = UnGraphMap::new();
 
@@ -96,9 +96,9 @@ fn compute_corners_seen_by_node(
 
         let node0 = in_group(node, dir0);
         let node1 = in_group(node, dir1);
-        if node0 && node1 && diag_not_in_group(node) {
-            count += 1;
-        } else if !node0 && !node1 {
+        if (node0 && node1 && diag_not_in_group(node))
+            || (!node0 && !node1)
+        {
             count += 1;
         }
     }
@@ -109,17 +109,14 @@ fn compute_corners_seen_by_node(
 fn parse(
     input: &str,
 ) -> miette::Result<HashMap<(i32, i32), char>> {
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, ch)| {
-                ((x as i32, y as i32), ch)
-            })
-        })
-        .collect::<HashMap<_, _>>();
-
-    Ok(grid)
+    let grid: Grid<char> = input
+        .parse()
+        .map_err(|err| ParseError::new(input, err))?;
+
+    Ok(grid
+        .iter_positions()
+        .map(|(pos, ch)| ((pos.x, pos.y), *ch))
+        .collect())
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use aoc_parse::{IResult, ParseError};
 use glam::UVec2;
 use nom::{
     bytes::complete::tag,
@@ -5,18 +6,17 @@ use nom::{
         complete,
         complete::{line_ending, one_of},
     },
-    combinator::{all_consuming, opt},
+    combinator::{all_consuming, cut, opt},
+    error::context,
     multi::{many1, separated_list1},
     sequence::{preceded, separated_pair, terminated},
-    IResult,
 };
 use pathfinding::prelude::*;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, machines) = parse(input).map_err(|err| {
-        miette::miette!("Parse error: {}", err)
-    })?;
+    let (_, machines) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let result = machines
         .iter()
@@ -37,31 +37,37 @@ struct Machine {
 impl Machine {
     const MAX_PUSHES: usize = 100;
 
-    fn parse(input: &str) -> IResult<&str, Machine> {
+    fn parse(input: &str) -> IResult<Machine> {
         let (input, (buttons, prize)) = separated_pair(
             separated_list1(
                 line_ending,
                 preceded(
                     tag("Button "),
-                    separated_pair(
-                        one_of("AB"),
-                        tag(": X+"),
+                    cut(context(
+                        "button",
                         separated_pair(
-                            complete::u32,
-                            tag(", Y+"),
-                            complete::u32,
+                            one_of("AB"),
+                            tag(": X+"),
+                            separated_pair(
+                                complete::u32,
+                                tag(", Y+"),
+                                complete::u32,
+                            ),
                         ),
-                    ),
+                    )),
                 ),
             ),
             line_ending,
             preceded(
                 tag("Prize: X="),
-                separated_pair(
-                    complete::u32,
-                    tag(", Y="),
-                    complete::u32,
-                ),
+                cut(context(
+                    "prize",
+                    separated_pair(
+                        complete::u32,
+                        tag(", Y="),
+                        complete::u32,
+                    ),
+                )),
             ),
         )(input)?;
 
@@ -127,7 +133,7 @@ impl Machine {
     }
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Machine>> {
+fn parse(input: &str) -> IResult<Vec<Machine>> {
     all_consuming(terminated(
         separated_list1(many1(line_ending), Machine::parse),
         opt(line_ending),
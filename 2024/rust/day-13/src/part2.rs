@@ -1,3 +1,4 @@
+use aoc_parse::{IResult, ParseError};
 use glam::U64Vec2;
 use nom::{
     bytes::complete::tag,
@@ -5,22 +6,20 @@ use nom::{
         complete,
         complete::{line_ending, one_of},
     },
-    combinator::{all_consuming, opt},
+    combinator::{all_consuming, cut, opt},
+    error::context,
     multi::{count, many1, separated_list1},
     sequence::{
         delimited, pair, preceded, separated_pair,
         terminated,
     },
-    IResult,
 };
 use std::cmp::Ordering;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, mut machines) =
-        parse(input).map_err(|err| {
-            miette::miette!("Parse error: {}", err)
-        })?;
+    let (_, mut machines) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     for machine in machines.iter_mut() {
         machine.prize += U64Vec2::splat(10000000000000);
@@ -49,31 +48,37 @@ struct Machine {
 }
 
 impl Machine {
-    fn parse(input: &str) -> IResult<&str, Machine> {
+    fn parse(input: &str) -> IResult<Machine> {
         let (input, (buttons, prize)) = pair(
             count(
                 delimited(
                     tag("Button "),
-                    separated_pair(
-                        one_of("AB"),
-                        tag(": X+"),
+                    cut(context(
+                        "button",
                         separated_pair(
-                            complete::u64,
-                            tag(", Y+"),
-                            complete::u64,
+                            one_of("AB"),
+                            tag(": X+"),
+                            separated_pair(
+                                complete::u64,
+                                tag(", Y+"),
+                                complete::u64,
+                            ),
                         ),
-                    ),
+                    )),
                     line_ending,
                 ),
                 2,
             ),
             preceded(
                 tag("Prize: X="),
-                separated_pair(
-                    complete::u64,
-                    tag(", Y="),
-                    complete::u64,
-                ),
+                cut(context(
+                    "prize",
+                    separated_pair(
+                        complete::u64,
+                        tag(", Y="),
+                        complete::u64,
+                    ),
+                )),
             ),
         )(input)?;
 
@@ -190,7 +195,7 @@ impl Machine {
     }
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Machine>> {
+fn parse(input: &str) -> IResult<Vec<Machine>> {
     all_consuming(terminated(
         separated_list1(many1(line_ending), Machine::parse),
         opt(line_ending),
@@ -0,0 +1,81 @@
+use aoc_input::InputArgs;
+use clap::{Parser, Subcommand};
+use day_14::{
+    export::{write_pgm, Simulation},
+    Params,
+};
+use std::{path::PathBuf, thread, time::Duration};
+
+/// Export the robots over a range of steps, the
+/// number of robots on a tile giving its
+/// brightness.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: Params,
+    /// first step to export
+    #[clap(long, default_value_t = 0)]
+    from: i64,
+    /// step after the last one to export
+    #[clap(long, default_value_t = 100)]
+    to: i64,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write a `step-N.pgm` image per step.
+    Pgm {
+        /// directory the images are written to
+        directory: PathBuf,
+    },
+    /// Play the steps in the terminal.
+    Play {
+        /// frames per second
+        #[clap(long, default_value_t = 10.0)]
+        fps: f64,
+    },
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 2)?;
+    let simulation = Simulation::new(&file, &args.params)?;
+    let steps = args.from..args.to;
+
+    match args.command {
+        Command::Pgm { directory } => {
+            let written =
+                write_pgm(&simulation, steps, &directory)?;
+            println!(
+                "wrote {written} images to {}",
+                directory.display()
+            );
+        }
+        Command::Play { fps } => {
+            if !fps.is_finite() || fps <= 0.0 {
+                miette::bail!(
+                    "the frame rate must be positive, got \
+                     {fps}"
+                );
+            }
+            let delay = Duration::from_secs_f64(1.0 / fps);
+            for (step, frame) in simulation.frames(steps) {
+                // clear the screen and go to its top
+                print!("\x1b[2J\x1b[H");
+                println!("step {step}");
+                print!("{}", frame.to_ansi());
+                thread::sleep(delay);
+            }
+        }
+    }
+    Ok(())
+}
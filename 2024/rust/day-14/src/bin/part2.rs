@@ -1,12 +1,17 @@
-use day_14::part2::process;
+use day_14::{part2::detect_with, Params};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
-    let file = include_str!("../../input2.txt");
-    let result = process(file).context("process part 2")?;
-    println!("{}", result);
+    let (file, params) = aoc_input::load_with::<Params>(
+        env!("CARGO_MANIFEST_DIR"),
+        2,
+    )?;
+    let detection = detect_with(&file, &params)
+        .context("process part 2")?;
+    print!("{}", detection.frame);
+    println!("{}", detection.step);
     Ok(())
 }
@@ -0,0 +1,276 @@
+//! Detection of the step the robots draw a
+//! picture, when they are the least spread out.
+//!
+//! The x of a robot repeats every `width` steps
+//! and its y every `height` steps, independently.
+//! Each axis is scored over a single period, the
+//! step where both are the most gathered is then
+//! the one matching both best offsets, found with
+//! the Chinese Remainder Theorem.
+use crate::part2::{render, Robot};
+use glam::IVec2;
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// How gathered the robots are along an axis,
+/// lower is more gathered.
+#[derive(
+    clap::ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+)]
+pub enum Score {
+    /// variance of the coordinates
+    #[default]
+    Variance,
+    /// Shannon entropy of the coordinates
+    Entropy,
+}
+
+impl Score {
+    fn dispersion(
+        self,
+        coordinates: impl Iterator<Item = i32>,
+        len: i32,
+    ) -> f64 {
+        match self {
+            Score::Variance => {
+                let (count, sum, squares) = coordinates
+                    .fold((0i64, 0i64, 0i64), |acc, x| {
+                        let x = x as i64;
+                        (
+                            acc.0 + 1,
+                            acc.1 + x,
+                            acc.2 + x * x,
+                        )
+                    });
+                if count == 0 {
+                    return 0.0;
+                }
+                (count * squares - sum * sum) as f64
+                    / (count * count) as f64
+            }
+            Score::Entropy => {
+                let mut histogram =
+                    vec![0usize; len as usize];
+                let mut count = 0;
+                for x in coordinates {
+                    histogram[x as usize] += 1;
+                    count += 1;
+                }
+                histogram
+                    .iter()
+                    .filter(|bin| **bin > 0)
+                    .map(|&bin| {
+                        let p = bin as f64 / count as f64;
+                        -p * p.ln()
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error, Diagnostic, PartialEq, Eq)]
+pub enum DetectError {
+    #[error(
+        "the robots gather on x at step {x} modulo \
+         {width} and on y at step {y} modulo {height}, \
+         which never happens at the same step"
+    )]
+    #[diagnostic(
+        code(day_14::misaligned),
+        help(
+            "the width and height share a factor, \
+             coprime sizes always have a solution"
+        )
+    )]
+    Misaligned {
+        x: i64,
+        width: i64,
+        y: i64,
+        height: i64,
+    },
+}
+
+/// Step the robots draw a picture, with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    pub step: i64,
+    /// `#` where there is a robot, `.` elsewhere
+    pub frame: String,
+}
+
+/// Offset in `0..len` where the robots are the
+/// most gathered along one axis.
+fn best_offset(
+    robots: &[Robot],
+    len: i32,
+    score: Score,
+    axis: impl Fn(IVec2) -> i32,
+) -> i64 {
+    (0..len)
+        .map(|step| {
+            let coordinates = robots.iter().map(|robot| {
+                (axis(robot.position)
+                    + step * axis(robot.velocity))
+                .rem_euclid(len)
+            });
+            (step, score.dispersion(coordinates, len))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(step, _)| step as i64)
+}
+
+/// `(gcd, x, y)` with `a * x + b * y = gcd`.
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+    let (gcd, x, y) = extended_gcd(b, a % b);
+    (gcd, y, x - (a / b) * y)
+}
+
+/// Smallest `t >= 0` with `t = a (mod m)` and
+/// `t = b (mod n)`.
+fn crt(a: i64, m: i64, b: i64, n: i64) -> Option<i64> {
+    let (gcd, x, _) = extended_gcd(m, n);
+    if (b - a) % gcd != 0 {
+        return None;
+    }
+    let lcm = m / gcd * n;
+    let k = ((b - a) / gcd * x).rem_euclid(n / gcd);
+    Some((a + m * k).rem_euclid(lcm))
+}
+
+/// Step in `0..width * height` the robots are the
+/// most gathered on both axes.
+pub(crate) fn detect(
+    robots: &[Robot],
+    size: IVec2,
+    score: Score,
+) -> Result<Detection, DetectError> {
+    let x = best_offset(robots, size.x, score, |v| v.x);
+    let y = best_offset(robots, size.y, score, |v| v.y);
+    let (width, height) = (size.x as i64, size.y as i64);
+    let step = crt(x, width, y, height).ok_or(
+        DetectError::Misaligned {
+            x,
+            width,
+            y,
+            height,
+        },
+    )?;
+
+    let frame = robots
+        .iter()
+        .map(|robot| Robot {
+            position: (robot.position
+                + robot.velocity
+                    * (step % (width * height)) as i32)
+                .rem_euclid(size),
+            velocity: robot.velocity,
+        })
+        .collect::<Vec<_>>();
+    Ok(Detection {
+        step,
+        frame: render(&frame, size),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part2::parse;
+    use rstest::rstest;
+
+    const SIZE: IVec2 = IVec2::new(11, 7);
+
+    /// Step the robots of `PICTURE` draw it.
+    const STEP: i32 = 40;
+
+    /// Robots as they are at `STEP`: a 4x3
+    /// rectangle at 3,2 and two strays, all
+    /// moving apart.
+    const PICTURE: &str = "p=3,2 v=-4,-1
+p=4,2 v=7,5
+p=5,2 v=0,-3
+p=6,2 v=2,-5
+p=3,3 v=1,3
+p=4,3 v=2,1
+p=5,3 v=6,4
+p=6,3 v=6,2
+p=3,4 v=3,5
+p=4,4 v=-5,1
+p=5,4 v=-1,3
+p=6,4 v=1,1
+p=0,0 v=-4,2
+p=10,6 v=6,4";
+
+    /// The robots of `PICTURE` back at step 0.
+    fn picture() -> Vec<Robot> {
+        let (_, robots) = parse(PICTURE).unwrap();
+        robots
+            .into_iter()
+            .map(|robot| Robot {
+                position: (robot.position
+                    - robot.velocity * STEP)
+                    .rem_euclid(SIZE),
+                velocity: robot.velocity,
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(Score::Variance)]
+    #[case(Score::Entropy)]
+    fn test_detect(#[case] score: Score) {
+        let detection =
+            detect(&picture(), SIZE, score).unwrap();
+        assert_eq!(STEP as i64, detection.step);
+        let lines =
+            detection.frame.lines().collect::<Vec<_>>();
+        assert_eq!(7, lines.len());
+        assert_eq!("...####....", lines[3]);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(
+            Some(6_789),
+            crt(6_789 % 101, 101, 6_789 % 103, 103)
+        );
+        assert_eq!(Some(10), crt(4, 6, 2, 4));
+        assert_eq!(None, crt(1, 6, 2, 4));
+    }
+
+    #[test]
+    fn test_same_as_brute_force() {
+        let size = SIZE;
+        let robots = picture();
+        let spread = |step: i32| {
+            let at = |axis: fn(IVec2) -> i32, len: i32| {
+                Score::Variance.dispersion(
+                    robots.iter().map(|robot| {
+                        (axis(robot.position)
+                            + step * axis(robot.velocity))
+                        .rem_euclid(len)
+                    }),
+                    len,
+                )
+            };
+            at(|v| v.x, size.x) + at(|v| v.y, size.y)
+        };
+        let best = (0..size.x * size.y)
+            .map(spread)
+            .min_by(f64::total_cmp)
+            .unwrap();
+        let detection =
+            detect(&robots, size, Score::Variance).unwrap();
+        assert_eq!(best, spread(detection.step as i32));
+    }
+}
@@ -0,0 +1,262 @@
+//! Frames of the robots over a range of steps,
+//! for looking through many steps at once.
+//!
+//! A frame holds the number of robots on each
+//! tile. It is written as a binary PGM image
+//! whose grey levels are those counts, or drawn
+//! in a terminal with ANSI grey backgrounds.
+use crate::{
+    part2::{parse, Robot},
+    Params,
+};
+use aoc_parse::ParseError;
+use glam::IVec2;
+use miette::{Context, IntoDiagnostic};
+use std::{fmt::Write, ops::Range, path::Path};
+
+/// Robots moving in the bathroom.
+#[derive(Debug)]
+pub struct Simulation {
+    robots: Vec<Robot>,
+    size: IVec2,
+}
+
+impl Simulation {
+    pub fn new(
+        input: &str,
+        params: &Params,
+    ) -> miette::Result<Self> {
+        let (_, robots) = parse(input)
+            .map_err(|err| ParseError::new(input, err))?;
+        Ok(Simulation {
+            robots,
+            size: IVec2::new(params.width, params.height),
+        })
+    }
+
+    /// Robots on each tile after `step` steps,
+    /// up to the 65535 grey levels of a PGM
+    /// image.
+    pub fn frame(&self, step: i64) -> Frame {
+        let size = self.size;
+        let mut counts =
+            vec![0u16; (size.x * size.y) as usize];
+        for robot in &self.robots {
+            // positions repeat on each axis, keep
+            // the product in range
+            let steps = IVec2::new(
+                step.rem_euclid(size.x as i64) as i32,
+                step.rem_euclid(size.y as i64) as i32,
+            );
+            let position = (robot.position
+                + robot.velocity * steps)
+                .rem_euclid(size);
+            let count = &mut counts[(position.y * size.x
+                + position.x)
+                as usize];
+            *count = count.saturating_add(1);
+        }
+        Frame { size, counts }
+    }
+
+    pub fn frames(
+        &self,
+        steps: Range<i64>,
+    ) -> impl Iterator<Item = (i64, Frame)> + '_ {
+        steps.map(|step| (step, self.frame(step)))
+    }
+}
+
+/// Number of robots on each tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    size: IVec2,
+    counts: Vec<u16>,
+}
+
+impl Frame {
+    pub fn count(&self, position: IVec2) -> u16 {
+        self.counts[(position.y * self.size.x + position.x)
+            as usize]
+    }
+
+    fn max(&self) -> u16 {
+        self.counts
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Binary PGM image, one pixel per tile with
+    /// the count as grey level, the most crowded
+    /// tile being white.
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let max = self.max();
+        let mut image = format!(
+            "P5\n{} {}\n{max}\n",
+            self.size.x, self.size.y
+        )
+        .into_bytes();
+        for &count in &self.counts {
+            // two bytes per pixel past 255, most
+            // significant first
+            if max > 255 {
+                image.extend(count.to_be_bytes());
+            } else {
+                image.push(count as u8);
+            }
+        }
+        image
+    }
+
+    /// Rows of two-space cells on the 24 level
+    /// ANSI grey ramp, lighter for more robots.
+    pub fn to_ansi(&self) -> String {
+        let max = self.max() as u32;
+        let mut text = String::new();
+        for row in self.counts.chunks(self.size.x as usize)
+        {
+            for &count in row {
+                if count == 0 {
+                    text.push_str("\x1b[0m  ");
+                } else {
+                    // from 233 for a single robot to 255
+                    let level = match max {
+                        1 => 255,
+                        _ => {
+                            233 + (count as u32 - 1) * 22
+                                / (max - 1)
+                        }
+                    };
+                    write!(text, "\x1b[48;5;{level}m  ")
+                        .unwrap();
+                }
+            }
+            text.push_str("\x1b[0m\n");
+        }
+        text
+    }
+}
+
+/// Writes a `step-N.pgm` image per step into
+/// `directory`, creating it if needed.
+pub fn write_pgm(
+    simulation: &Simulation,
+    steps: Range<i64>,
+    directory: &Path,
+) -> miette::Result<usize> {
+    std::fs::create_dir_all(directory)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!("creating `{}`", directory.display())
+        })?;
+    let mut written = 0;
+    for (step, frame) in simulation.frames(steps) {
+        let path =
+            directory.join(format!("step-{step}.pgm"));
+        std::fs::write(&path, frame.to_pgm())
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("writing `{}`", path.display())
+            })?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "p=0,0 v=1,0
+p=0,0 v=1,0
+p=2,1 v=0,1";
+
+    fn simulation() -> Simulation {
+        Simulation::new(
+            INPUT,
+            &Params {
+                width: 3,
+                height: 2,
+                ..Params::EXAMPLE
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_frame() {
+        let simulation = simulation();
+        let frame = simulation.frame(1);
+        assert_eq!(2, frame.count(IVec2::new(1, 0)));
+        assert_eq!(1, frame.count(IVec2::new(2, 0)));
+        assert_eq!(0, frame.count(IVec2::new(0, 0)));
+        // far steps wrap around like close ones
+        assert_eq!(
+            frame,
+            simulation.frame(1 + 6 * 1_000_000_007)
+        );
+        assert_eq!(
+            simulation.frame(5),
+            simulation.frame(-1)
+        );
+    }
+
+    #[test]
+    fn test_crowded_frame() {
+        let input = "p=0,0 v=0,0\n".repeat(70_000);
+        let simulation = Simulation::new(
+            &input,
+            &Params {
+                width: 1,
+                height: 1,
+                ..Params::EXAMPLE
+            },
+        )
+        .unwrap();
+        let frame = simulation.frame(0);
+        assert_eq!(u16::MAX, frame.count(IVec2::ZERO));
+        assert!(frame
+            .to_pgm()
+            .starts_with(b"P5\n1 1\n65535\n"));
+    }
+
+    #[test]
+    fn test_pgm() {
+        assert_eq!(
+            b"P5\n3 2\n2\n\x02\x00\x00\x00\x00\x01"
+                .to_vec(),
+            simulation().frame(0).to_pgm()
+        );
+    }
+
+    #[test]
+    fn test_ansi() {
+        let ansi = simulation().frame(0).to_ansi();
+        assert_eq!(2, ansi.lines().count());
+        assert!(
+            ansi.starts_with("\x1b[48;5;255m  \x1b[0m  ")
+        );
+        assert!(ansi.contains("\x1b[48;5;233m  "));
+    }
+
+    #[test]
+    fn test_write_pgm() {
+        let directory = std::env::temp_dir().join(format!(
+            "day-14-export-{}",
+            std::process::id()
+        ));
+        let written =
+            write_pgm(&simulation(), 3..6, &directory)
+                .unwrap();
+        assert_eq!(3, written);
+        assert_eq!(
+            simulation().frame(4).to_pgm(),
+            std::fs::read(directory.join("step-4.pgm"))
+                .unwrap()
+        );
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,2 +1,48 @@
+use easter_egg::Score;
+
+pub mod easter_egg;
+pub mod export;
 pub mod part1;
 pub mod part2;
+
+/// Puzzle parameters, the defaults are the real
+/// puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// width of the bathroom, in tiles
+    #[clap(
+        long,
+        default_value_t = Params::default().width,
+        value_parser = clap::value_parser!(i32).range(1..)
+    )]
+    pub width: i32,
+    /// height of the bathroom, in tiles
+    #[clap(
+        long,
+        default_value_t = Params::default().height,
+        value_parser = clap::value_parser!(i32).range(1..)
+    )]
+    pub height: i32,
+    /// how part 2 scores the spread of the robots
+    #[clap(long, value_enum, default_value_t = Params::default().score)]
+    pub score: Score,
+}
+
+impl Params {
+    /// Parameters of the puzzle's example.
+    pub const EXAMPLE: Params = Params {
+        width: 11,
+        height: 7,
+        score: Score::Variance,
+    };
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            width: 101,
+            height: 103,
+            score: Score::Variance,
+        }
+    }
+}
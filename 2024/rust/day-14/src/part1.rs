@@ -1,34 +1,35 @@
+use crate::Params;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
-use itertools::repeat_n;
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
-    combinator::{all_consuming, opt},
+    combinator::{all_consuming, cut, opt},
+    error::context,
     multi::separated_list1,
     sequence::{preceded, separated_pair, terminated},
-    IResult,
 };
 
-#[cfg(test)]
-const MAP_SIZE: IVec2 = IVec2::new(11, 7);
-#[cfg(not(test))]
-const MAP_SIZE: IVec2 = IVec2::new(101, 103);
-
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, mut robots) = parse(input).map_err(|err| {
-        miette::miette!("Parse error: {}", err)
-    })?;
+    process_with(input, &Params::default())
+}
 
-    println!("Initial state:");
-    show_bots_on_grid(&robots);
+#[tracing::instrument(skip(input))]
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<String> {
+    let map_size = IVec2::new(params.width, params.height);
+    let (_, mut robots) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
-    move_robots(&mut robots, 100);
+    move_robots(&mut robots, 100, map_size);
 
-    let west_range = 0..(MAP_SIZE.x - 1) / 2;
-    let east_range = ((MAP_SIZE.x - 1) / 2 + 1)..MAP_SIZE.x;
-    let north_range = 0..(MAP_SIZE.y - 1) / 2;
-    let south_range = (MAP_SIZE.y - 1) / 2 + 1..MAP_SIZE.y;
+    let west_range = 0..(map_size.x - 1) / 2;
+    let east_range = ((map_size.x - 1) / 2 + 1)..map_size.x;
+    let north_range = 0..(map_size.y - 1) / 2;
+    let south_range = (map_size.y - 1) / 2 + 1..map_size.y;
 
     let result = robots
         .iter()
@@ -65,61 +66,49 @@ struct Robot {
 }
 
 impl Robot {
-    fn move_step(&mut self, steps: usize) {
+    fn move_step(&mut self, steps: usize, map_size: IVec2) {
         self.position.x = (self.position.x
             + (steps as i32) * self.velocity.x)
-            .checked_rem_euclid(MAP_SIZE.x)
+            .checked_rem_euclid(map_size.x)
             .expect("Too many steps");
         self.position.y = (self.position.y
             + (steps as i32) * self.velocity.y)
-            .checked_rem_euclid(MAP_SIZE.y)
+            .checked_rem_euclid(map_size.y)
             .expect("Too many steps");
     }
 }
 
-fn show_bots_on_grid(robots: &[Robot]) {
-    let mut grid: Vec<Vec<char>> = repeat_n(
-        repeat_n('.', MAP_SIZE.x as usize).collect(),
-        MAP_SIZE.y as usize,
-    )
-    .collect();
-
-    for robot in robots {
-        grid[robot.position.y as usize]
-            [robot.position.x as usize] = '#';
-    }
-
-    for line in grid.iter() {
-        println!("{}", line.iter().collect::<String>());
+fn move_robots(
+    robots: &mut [Robot],
+    steps: usize,
+    map_size: IVec2,
+) {
+    for robot in robots.iter_mut() {
+        robot.move_step(steps, map_size);
     }
 }
 
-fn move_robots(robots: &mut Vec<Robot>, steps: usize) {
-    for robot in robots.iter_mut() {
-        robot.move_step(steps);
-    }
+fn vector(input: &str) -> IResult<(i32, i32)> {
+    separated_pair(complete::i32, tag(","), complete::i32)(
+        input,
+    )
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Robot>> {
+fn parse(input: &str) -> IResult<Vec<Robot>> {
     let (input, robots) = all_consuming(terminated(
         separated_list1(
             line_ending,
-            separated_pair(
-                preceded(
-                    tag("p="),
-                    separated_pair(
-                        complete::i32,
-                        tag(","),
-                        complete::i32,
+            context(
+                "robot",
+                separated_pair(
+                    preceded(
+                        tag("p="),
+                        cut(context("position", vector)),
                     ),
-                ),
-                tag(" "),
-                preceded(
-                    tag("v="),
-                    separated_pair(
-                        complete::i32,
-                        tag(","),
-                        complete::i32,
+                    tag(" "),
+                    preceded(
+                        tag("v="),
+                        cut(context("velocity", vector)),
                     ),
                 ),
             ),
@@ -156,7 +145,22 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
-        assert_eq!("12", process(input)?);
+        assert_eq!(
+            "12",
+            process_with(input, &Params::EXAMPLE)?
+        );
         Ok(())
     }
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "p=0,4 v=3,-3\np=6,x v=-1,-3\n";
+        let err = parse(input)
+            .map_err(|err| ParseError::new(input, err))
+            .unwrap_err();
+        assert_eq!(
+            (Some(2), Some(5)),
+            (err.line, err.column)
+        );
+    }
 }
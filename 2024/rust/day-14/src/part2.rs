@@ -1,62 +1,61 @@
+use crate::{
+    easter_egg::{detect, Detection},
+    Params,
+};
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
-use itertools::{repeat_n, Itertools};
+use itertools::repeat_n;
 use nom::{
     bytes::complete::tag,
     character::{complete, complete::line_ending},
-    combinator::{all_consuming, opt},
+    combinator::{all_consuming, cut, opt},
+    error::context,
     multi::separated_list1,
     sequence::{preceded, separated_pair, terminated},
-    IResult,
 };
 
-#[cfg(test)]
-const MAP_SIZE: IVec2 = IVec2::new(11, 7);
-#[cfg(not(test))]
-const MAP_SIZE: IVec2 = IVec2::new(101, 103);
-
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, mut robots) = parse(input).map_err(|err| {
-        miette::miette!("Parse error: {}", err)
-    })?;
-
-    println!("Initial state:");
-    show_bots_on_grid(&robots);
-
-    let mut step = 0;
-    while !robots
-        .iter()
-        .map(|robot| robot.position)
-        .all_unique()
-    {
-        move_robots(&mut robots, 1);
-        step += 1;
-    }
-
-    println!("Final state:");
-    show_bots_on_grid(&robots);
+    process_with(input, &Params::default())
+}
 
-    Ok(step.to_string())
+#[tracing::instrument(skip(input))]
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<String> {
+    Ok(detect_with(input, params)?.step.to_string())
 }
 
-#[derive(Debug)]
-struct Robot {
-    position: IVec2,
-    velocity: IVec2,
+/// Step the robots draw a picture, with the
+/// picture.
+#[tracing::instrument(skip(input))]
+pub fn detect_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<Detection> {
+    let map_size = IVec2::new(params.width, params.height);
+    let (_, robots) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
+
+    Ok(detect(&robots, map_size, params.score)?)
 }
 
-impl Robot {
-    fn move_step(&mut self, steps: usize) {
-        self.position = (self.position
-            + (steps as i32) * self.velocity)
-            .rem_euclid(MAP_SIZE);
-    }
+#[derive(Debug)]
+pub(crate) struct Robot {
+    pub(crate) position: IVec2,
+    pub(crate) velocity: IVec2,
 }
 
-fn show_bots_on_grid(robots: &[Robot]) {
+/// Rows of the bathroom, `#` where there is a
+/// robot.
+pub(crate) fn render(
+    robots: &[Robot],
+    map_size: IVec2,
+) -> String {
     let mut grid: Vec<Vec<char>> = repeat_n(
-        repeat_n('.', MAP_SIZE.x as usize).collect(),
-        MAP_SIZE.y as usize,
+        repeat_n('.', map_size.x as usize).collect(),
+        map_size.y as usize,
     )
     .collect();
 
@@ -65,37 +64,32 @@ fn show_bots_on_grid(robots: &[Robot]) {
             [robot.position.x as usize] = '#';
     }
 
-    for line in grid.iter() {
-        println!("{}", line.iter().collect::<String>());
-    }
+    grid.iter()
+        .map(|line| line.iter().collect::<String>() + "\n")
+        .collect()
 }
 
-fn move_robots(robots: &mut Vec<Robot>, steps: usize) {
-    for robot in robots.iter_mut() {
-        robot.move_step(steps);
-    }
+fn vector(input: &str) -> IResult<(i32, i32)> {
+    separated_pair(complete::i32, tag(","), complete::i32)(
+        input,
+    )
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Robot>> {
+pub(crate) fn parse(input: &str) -> IResult<Vec<Robot>> {
     let (input, robots) = all_consuming(terminated(
         separated_list1(
             line_ending,
-            separated_pair(
-                preceded(
-                    tag("p="),
-                    separated_pair(
-                        complete::i32,
-                        tag(","),
-                        complete::i32,
+            context(
+                "robot",
+                separated_pair(
+                    preceded(
+                        tag("p="),
+                        cut(context("position", vector)),
                     ),
-                ),
-                tag(" "),
-                preceded(
-                    tag("v="),
-                    separated_pair(
-                        complete::i32,
-                        tag(","),
-                        complete::i32,
+                    tag(" "),
+                    preceded(
+                        tag("v="),
+                        cut(context("velocity", vector)),
                     ),
                 ),
             ),
@@ -132,7 +126,12 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
-        assert_eq!("1", process(input)?);
+        // the example draws no picture, this is
+        // just where its robots are the closest
+        assert_eq!(
+            "24",
+            process_with(input, &Params::EXAMPLE)?
+        );
         Ok(())
     }
 }
@@ -0,0 +1,173 @@
+use aoc_input::InputArgs;
+use clap::Parser;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    terminal::{
+        self, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use day_15::{
+    session::Session, warehouse::Direction, Params,
+};
+use miette::{Context, IntoDiagnostic};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Move the robot of the warehouse by hand,
+/// or step through a move list.
+///
+/// Arrows or `^v<>` move the robot, `u`/`r` undo
+/// and redo, `b`/`e` go to the beginning and the
+/// end of the moves, `s` saves and `q` quits.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: Params,
+    /// move list to step through instead of the
+    /// input's
+    #[clap(long)]
+    moves: Option<PathBuf>,
+    /// file `s` saves the session to, as a puzzle
+    /// input
+    #[clap(long, default_value = "session.txt")]
+    save: PathBuf,
+}
+
+/// Raw mode on the alternate screen, restored
+/// when dropped.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(
+            io::stdout(),
+            Show,
+            LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn draw(
+    session: &Session,
+    message: &str,
+) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    queue!(
+        stdout,
+        Clear(ClearType::All),
+        MoveTo(0, 0)
+    )?;
+    // raw mode does not return to the first column
+    for line in session.to_string().lines() {
+        write!(stdout, "{line}\r\n")?;
+    }
+    write!(
+        stdout,
+        "\r\nstep {}/{}  GPS {}  {message}\r\n",
+        session.step(),
+        session.step() + session.remaining(),
+        session.gps(),
+    )?;
+    write!(
+        stdout,
+        "arrows ^v<> move  u/r undo/redo  b/e \
+         beginning/end  s save  q quit\r\n"
+    )?;
+    stdout.flush()
+}
+
+fn direction(code: KeyCode) -> Option<Direction> {
+    match code {
+        KeyCode::Up => Some(Direction::North),
+        KeyCode::Down => Some(Direction::South),
+        KeyCode::Left => Some(Direction::West),
+        KeyCode::Right => Some(Direction::East),
+        KeyCode::Char(ch) => Direction::from_char(ch),
+        _ => None,
+    }
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 2)?;
+    let mut session =
+        Session::new(&file, args.params.scale)?;
+    if let Some(path) = &args.moves {
+        let moves = aoc_input::read(path)?;
+        session.load(&moves).context("loading moves")?;
+    }
+
+    let _screen = Screen::enter().into_diagnostic()?;
+    let mut message = String::new();
+    loop {
+        draw(&session, &message).into_diagnostic()?;
+        message.clear();
+
+        let Event::Key(key) =
+            event::read().into_diagnostic()?
+        else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if let Some(direction) = direction(key.code) {
+            if !session.push(direction) {
+                message.push_str("blocked");
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('u') => {
+                session.undo();
+            }
+            KeyCode::Char('r') => {
+                session.redo();
+            }
+            KeyCode::Char('b') => {
+                while session.undo().is_some() {}
+            }
+            KeyCode::Char('e') => {
+                while session.redo().is_some() {}
+            }
+            KeyCode::Char('s') => {
+                message = match std::fs::write(
+                    &args.save,
+                    session.save(),
+                ) {
+                    Ok(()) => format!(
+                        "saved to {}",
+                        args.save.display()
+                    ),
+                    Err(err) => {
+                        format!("save failed: {err}")
+                    }
+                };
+            }
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use aoc_input::InputArgs;
+use clap::Parser;
+use day_15::{replay::Replay, Params};
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use std::path::PathBuf;
+
+/// Report what each move of the input did: the
+/// moves blocked by a wall, the boxes pushed and
+/// where everything ended up.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: Params,
+    /// write the input without its blocked moves
+    /// there, it ends in the same warehouse
+    #[clap(long)]
+    compress: Option<PathBuf>,
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 2)?;
+    let replay = Replay::new(&file, args.params.scale)?;
+
+    let moves = replay.moves();
+    let pushing = moves
+        .iter()
+        .filter(|step| step.pushed.is_some_and(|n| n > 0))
+        .count();
+    let most = moves
+        .iter()
+        .filter_map(|step| step.pushed)
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{} moves, {} blocked, {pushing} pushing up to \
+         {most} boxes",
+        moves.len(),
+        replay.blocked().count(),
+    );
+    println!(
+        "blocked: {}",
+        replay.blocked().join(" ")
+    );
+    print!("{}", replay.diff());
+    print!("{}", replay.last());
+
+    if let Some(path) = &args.compress {
+        std::fs::write(path, replay.compressed_input())
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("writing `{}`", path.display())
+            })?;
+    }
+    Ok(())
+}
@@ -1,2 +1,25 @@
 pub mod part1;
 pub mod part2;
+pub mod replay;
+pub mod session;
+pub mod warehouse;
+
+/// Puzzle parameters, the defaults are the real
+/// puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// how many tiles wide each tile of the map
+    /// becomes in part 2
+    #[clap(
+        long,
+        default_value_t = Params::default().scale,
+        value_parser = clap::value_parser!(i32).range(1..)
+    )]
+    pub scale: i32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { scale: 2 }
+    }
+}
@@ -0,0 +1,234 @@
+//! What each move of a move list did, to debug
+//! a wrong answer or shrink a reproduction case.
+//!
+//! A replay plays the moves once, recording the
+//! boxes each of them pushed. Blocked moves
+//! change nothing, so the moves without them end
+//! in the same warehouse.
+use crate::{
+    session::{map_text, puzzle_input},
+    warehouse::{parse, Direction, Warehouse},
+};
+use glam::IVec2;
+use std::fmt::{self, Display, Formatter};
+
+/// A move of the list and what it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction,
+    /// number of boxes pushed, `None` when the
+    /// robot was blocked
+    pub pushed: Option<usize>,
+}
+
+/// A box that is not where it started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Displaced {
+    pub index: usize,
+    /// top left corners
+    pub from: IVec2,
+    pub to: IVec2,
+}
+
+/// Changes between two states of a warehouse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// where the robot was and is
+    pub robot: (IVec2, IVec2),
+    pub boxes: Vec<Displaced>,
+}
+
+impl Diff {
+    pub fn new(from: &Warehouse, to: &Warehouse) -> Self {
+        let boxes = from
+            .corners()
+            .zip(to.corners())
+            .enumerate()
+            .filter(|(_, (from, to))| from != to)
+            .map(|(index, (from, to))| Displaced {
+                index,
+                from,
+                to,
+            })
+            .collect();
+        Diff {
+            robot: (from.robot(), to.robot()),
+            boxes,
+        }
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (from, to) = self.robot;
+        writeln!(f, "robot {from} -> {to}")?;
+        for Displaced { index, from, to } in &self.boxes {
+            writeln!(f, "box {index} {from} -> {to}")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Replay {
+    /// the map as written in the input
+    map: String,
+    initial: Warehouse,
+    last: Warehouse,
+    moves: Vec<Move>,
+}
+
+impl Replay {
+    /// Plays the moves of the input, the map
+    /// widened by `scale`.
+    pub fn new(
+        input: &str,
+        scale: i32,
+    ) -> miette::Result<Self> {
+        let (initial, directions) = parse(input, scale)?;
+        let mut last = initial.clone();
+        let moves = directions
+            .into_iter()
+            .map(|direction| Move {
+                direction,
+                pushed: last
+                    .step(direction)
+                    .map(|pushed| pushed.len()),
+            })
+            .collect();
+        Ok(Replay {
+            map: map_text(input),
+            initial,
+            last,
+            moves,
+        })
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn initial(&self) -> &Warehouse {
+        &self.initial
+    }
+
+    /// The warehouse after every move.
+    pub fn last(&self) -> &Warehouse {
+        &self.last
+    }
+
+    /// Indices of the moves blocked by a wall.
+    pub fn blocked(
+        &self,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.moves
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| step.pushed.is_none())
+            .map(|(index, _)| index)
+    }
+
+    /// Where the robot and the boxes moved from
+    /// the initial warehouse.
+    pub fn diff(&self) -> Diff {
+        Diff::new(&self.initial, &self.last)
+    }
+
+    /// The moves that were not blocked, ending in
+    /// the same warehouse.
+    pub fn compressed(&self) -> Vec<Direction> {
+        self.moves
+            .iter()
+            .filter(|step| step.pushed.is_some())
+            .map(|step| step.direction)
+            .collect()
+    }
+
+    /// Puzzle input of the map with the
+    /// compressed moves, which only holds at the
+    /// same scale.
+    pub fn compressed_input(&self) -> String {
+        puzzle_input(&self.map, self.compressed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const INPUT: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+    #[test]
+    fn test_moves() {
+        let replay = Replay::new(INPUT, 1).unwrap();
+        // the walkthrough of the puzzle
+        assert_eq!(
+            vec![0, 2, 5, 7, 14],
+            replay.blocked().collect::<Vec<_>>()
+        );
+        let pushed = replay
+            .moves()
+            .iter()
+            .map(|step| step.pushed.unwrap_or(0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                0, 0, 0, 1, 2, 0, 4, 0, 0, 0, 1, 1, 0, 1, 0
+            ],
+            pushed
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let replay = Replay::new(INPUT, 1).unwrap();
+        let diff = replay.diff();
+        assert_eq!(
+            (IVec2::new(2, 2), IVec2::new(4, 4)),
+            diff.robot
+        );
+        assert_eq!(
+            Displaced {
+                index: 0,
+                from: IVec2::new(3, 1),
+                to: IVec2::new(5, 1),
+            },
+            diff.boxes[0]
+        );
+        assert!(diff.to_string().starts_with(
+            "robot [2, 2] -> [4, 4]\nbox 0 [3, 1] -> [5, 1]\n"
+        ));
+        assert!(Diff::new(
+            replay.initial(),
+            replay.initial()
+        )
+        .boxes
+        .is_empty());
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn test_compressed(#[case] scale: i32) {
+        let replay = Replay::new(INPUT, scale).unwrap();
+        let compressed =
+            Replay::new(&replay.compressed_input(), scale)
+                .unwrap();
+        assert_eq!(replay.last(), compressed.last());
+        assert_eq!(0, compressed.blocked().count());
+        assert_eq!(
+            replay.moves().len() - replay.blocked().count(),
+            compressed.moves().len()
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Moving the robot of a warehouse one step at
+//! a time, with undo and redo.
+//!
+//! Every move records the boxes it pushed, so
+//! undoing it pushes them back without keeping
+//! copies of the warehouse. A loaded move list is
+//! kept as the moves to redo, stepping through it
+//! is redoing and undoing.
+use crate::warehouse::{parse, Direction, Warehouse};
+use std::fmt::{self, Display, Formatter};
+
+/// Moves per line of a saved session, like the
+/// puzzle's example.
+const LINE_WIDTH: usize = 70;
+
+struct Record {
+    direction: Direction,
+    /// boxes pushed by the move, `None` when it
+    /// was blocked
+    pushed: Option<Vec<usize>>,
+}
+
+pub struct Session {
+    /// the map as written in the input
+    map: String,
+    warehouse: Warehouse,
+    history: Vec<Record>,
+    /// moves to redo, the next one last
+    future: Vec<Direction>,
+}
+
+impl Session {
+    /// Starts before the first move of the input,
+    /// its moves being loaded, the map widened by
+    /// `scale`.
+    pub fn new(
+        input: &str,
+        scale: i32,
+    ) -> miette::Result<Self> {
+        let (warehouse, directions) = parse(input, scale)?;
+        Ok(Session {
+            map: map_text(input),
+            warehouse,
+            history: vec![],
+            future: directions.into_iter().rev().collect(),
+        })
+    }
+
+    /// Replaces the moves to redo with `moves`,
+    /// written like in the puzzle input.
+    pub fn load(
+        &mut self,
+        moves: &str,
+    ) -> miette::Result<()> {
+        let directions = moves
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .enumerate()
+            .map(|(index, ch)| {
+                Direction::from_char(ch).ok_or_else(|| {
+                    miette::miette!(
+                        "move {index} `{ch}` is not one of \
+                         `^v<>`"
+                    )
+                })
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+        self.future =
+            directions.into_iter().rev().collect();
+        Ok(())
+    }
+
+    fn apply(&mut self, direction: Direction) -> bool {
+        let pushed = self.warehouse.step(direction);
+        let moved = pushed.is_some();
+        self.history.push(Record { direction, pushed });
+        moved
+    }
+
+    /// Moves the robot, forgetting the moves to
+    /// redo. Returns whether it moved.
+    pub fn push(&mut self, direction: Direction) -> bool {
+        self.future.clear();
+        self.apply(direction)
+    }
+
+    /// Takes back the last move.
+    pub fn undo(&mut self) -> Option<Direction> {
+        let Record { direction, pushed } =
+            self.history.pop()?;
+        if let Some(pushed) = pushed {
+            self.warehouse.unstep(direction, &pushed);
+        }
+        self.future.push(direction);
+        Some(direction)
+    }
+
+    /// Plays the next move to redo.
+    pub fn redo(&mut self) -> Option<Direction> {
+        let direction = self.future.pop()?;
+        self.apply(direction);
+        Some(direction)
+    }
+
+    /// Number of moves played.
+    pub fn step(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Number of moves left to redo.
+    pub fn remaining(&self) -> usize {
+        self.future.len()
+    }
+
+    pub fn gps(&self) -> i32 {
+        self.warehouse.gps()
+    }
+
+    /// Moves played, without line breaks.
+    pub fn moves(&self) -> String {
+        self.history
+            .iter()
+            .map(|record| record.direction.to_char())
+            .collect()
+    }
+
+    /// Puzzle input replaying the moves played
+    /// from the initial map.
+    pub fn save(&self) -> String {
+        puzzle_input(
+            &self.map,
+            self.history
+                .iter()
+                .map(|record| record.direction),
+        )
+    }
+}
+
+/// The map of a puzzle input, as written.
+pub(crate) fn map_text(input: &str) -> String {
+    input
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Puzzle input of `map` followed by `moves`.
+pub(crate) fn puzzle_input(
+    map: &str,
+    moves: impl IntoIterator<Item = Direction>,
+) -> String {
+    let moves = moves
+        .into_iter()
+        .map(Direction::to_char)
+        .collect::<Vec<_>>();
+    let mut text = format!("{map}\n\n");
+    for line in moves.chunks(LINE_WIDTH) {
+        text.extend(line);
+        text.push('\n');
+    }
+    text
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.warehouse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part2::process;
+
+    const INPUT: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+    #[test]
+    fn test_step_through() {
+        let mut session = Session::new(INPUT, 2).unwrap();
+        let initial = session.to_string();
+        let initial_gps = session.gps();
+        assert_eq!(700, session.remaining());
+
+        while session.redo().is_some() {}
+        assert_eq!(9021, session.gps());
+        assert_eq!(700, session.step());
+        let last = session.to_string();
+
+        while session.undo().is_some() {}
+        assert_eq!(initial, session.to_string());
+        assert_eq!(initial_gps, session.gps());
+
+        while session.redo().is_some() {}
+        assert_eq!(last, session.to_string());
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let mut session = Session::new(INPUT, 2).unwrap();
+        session.redo();
+        session.undo();
+        assert_eq!(700, session.remaining());
+        // the bot starts with a box on its left
+        assert!(session.push(Direction::West));
+        assert_eq!(0, session.remaining());
+        assert_eq!(Some(Direction::West), session.undo());
+        assert_eq!(1, session.remaining());
+    }
+
+    #[test]
+    fn test_save() {
+        let mut session = Session::new(INPUT, 2).unwrap();
+        session.load("<<^\n^>>vv").unwrap();
+        while session.redo().is_some() {}
+        let saved = session.save();
+        assert!(saved.ends_with("\n\n<<^^>>vv\n"));
+        assert_eq!(
+            session.gps().to_string(),
+            process(&saved).unwrap()
+        );
+        assert!(session.load("<x").is_err());
+    }
+}
@@ -0,0 +1,530 @@
+//! Warehouse of boxes pushed by a robot, shared
+//! by both parts.
+//!
+//! A box is any shape of tiles:
+//!
+//! - `O` is a single tile box,
+//! - `[` up to the next `]` on the row, with `=`
+//!   in between, is a box as wide as that,
+//! - tiles of any other letter touching each
+//!   other form one box, e.g. an L of `a`s.
+//!
+//! The map can be widened by a scale factor,
+//! every tile becoming that many tiles: part 1 is
+//! scale 1 and part 2 scale 2. A push goes
+//! through every box touched by the ones already
+//! pushed, all of them moving unless one of them
+//! hits a wall.
+use aoc_grid::{parser::grid, Grid};
+use aoc_parse::{IResult, ParseError};
+use glam::IVec2;
+use miette::Diagnostic;
+use nom::{
+    character::complete::{line_ending, one_of},
+    combinator::{all_consuming, map, opt},
+    multi::{many0, separated_list0},
+    sequence::{pair, separated_pair, terminated},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display, Formatter},
+};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Direction {
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '^' => Some(Self::North),
+            '<' => Some(Self::West),
+            'v' => Some(Self::South),
+            '>' => Some(Self::East),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::North => '^',
+            Self::West => '<',
+            Self::South => 'v',
+            Self::East => '>',
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::West => Self::East,
+            Self::South => Self::North,
+            Self::East => Self::West,
+        }
+    }
+
+    pub fn offset(self) -> IVec2 {
+        match self {
+            Self::North => IVec2::NEG_Y,
+            Self::West => IVec2::NEG_X,
+            Self::South => IVec2::Y,
+            Self::East => IVec2::X,
+        }
+    }
+
+    fn parse(input: &str) -> IResult<Self> {
+        let (input, dir) = one_of("^<v>")(input)?;
+
+        let dir = Self::from_char(dir)
+            .expect("one_of only accepts directions");
+
+        Ok((input, dir))
+    }
+}
+
+#[derive(
+    Debug, Clone, Error, Diagnostic, PartialEq, Eq,
+)]
+pub enum MapError {
+    #[error("the map has no robot `@`")]
+    #[diagnostic(code(day_15::no_robot))]
+    NoRobot,
+    #[error(
+        "the map has a robot at {first} and another at \
+         {second}"
+    )]
+    #[diagnostic(code(day_15::several_robots))]
+    SeveralRobots { first: IVec2, second: IVec2 },
+    #[error(
+        "`{found}` at {position} is not part of a \
+         `[...]` box"
+    )]
+    #[diagnostic(
+        code(day_15::unmatched_bracket),
+        help(
+            "wide boxes are written `[]`, `[=]`, `[==]`..."
+        )
+    )]
+    UnmatchedBracket { position: IVec2, found: char },
+}
+
+/// Tiles of a box, with the character drawing
+/// each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Shape {
+    tiles: Vec<(IVec2, char)>,
+}
+
+impl Shape {
+    /// Top left corner of the box's bounding box.
+    fn corner(&self) -> IVec2 {
+        self.tiles
+            .iter()
+            .map(|(position, _)| *position)
+            .reduce(IVec2::min)
+            .expect("boxes have tiles")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+    size: IVec2,
+    walls: HashSet<IVec2>,
+    boxes: Vec<Shape>,
+    /// box on each tile
+    occupied: HashMap<IVec2, usize>,
+    robot: IVec2,
+}
+
+/// Characters of a `width` tiles wide box.
+fn wide_box(width: i32) -> impl Iterator<Item = char> {
+    (0..width).map(move |x| match x {
+        _ if width == 1 => 'O',
+        0 => '[',
+        x if x == width - 1 => ']',
+        _ => '=',
+    })
+}
+
+impl Warehouse {
+    /// Builds the warehouse of `map`, every tile
+    /// becoming `scale` tiles wide.
+    pub fn new(
+        map: &Grid<char>,
+        scale: i32,
+    ) -> Result<Self, MapError> {
+        let mut walls = HashSet::new();
+        let mut boxes = vec![];
+        let mut robot = None;
+        // tiles of the map already in a box
+        let mut boxed = HashSet::new();
+
+        let widen = |position: IVec2| {
+            (0..scale).map(move |x| {
+                IVec2::new(
+                    position.x * scale + x,
+                    position.y,
+                )
+            })
+        };
+
+        for (position, &tile) in map.iter_positions() {
+            match tile {
+                '#' => walls.extend(widen(position)),
+                '@' => {
+                    let here =
+                        widen(position).next().unwrap();
+                    if let Some(first) = robot {
+                        return Err(
+                            MapError::SeveralRobots {
+                                first,
+                                second: here,
+                            },
+                        );
+                    }
+                    robot = Some(here);
+                }
+                'O' => boxes.push(Shape {
+                    tiles: widen(position)
+                        .zip(wide_box(scale))
+                        .collect(),
+                }),
+                '[' => {
+                    let end = (position.x + 1..)
+                        .map(|x| IVec2::new(x, position.y))
+                        .find(|end| {
+                            map.get(*end) != Some(&'=')
+                        })
+                        .filter(|end| {
+                            map.get(*end) == Some(&']')
+                        })
+                        .ok_or(
+                            MapError::UnmatchedBracket {
+                                position,
+                                found: tile,
+                            },
+                        )?;
+                    let width =
+                        (end.x - position.x + 1) * scale;
+                    boxed.extend((position.x..=end.x).map(
+                        |x| IVec2::new(x, position.y),
+                    ));
+                    let start =
+                        widen(position).next().unwrap();
+                    boxes.push(Shape {
+                        tiles: (0..width)
+                            .map(|x| start + IVec2::X * x)
+                            .zip(wide_box(width))
+                            .collect(),
+                    });
+                }
+                '=' | ']' if !boxed.contains(&position) => {
+                    return Err(
+                        MapError::UnmatchedBracket {
+                            position,
+                            found: tile,
+                        },
+                    );
+                }
+                letter
+                    if letter.is_alphabetic()
+                        && !boxed.contains(&position) =>
+                {
+                    // flood the touching tiles of the
+                    // same letter
+                    let mut tiles = vec![];
+                    let mut queue =
+                        VecDeque::from([position]);
+                    boxed.insert(position);
+                    while let Some(tile) = queue.pop_front()
+                    {
+                        tiles
+                            .extend(widen(tile).map(
+                                |tile| (tile, letter),
+                            ));
+                        for (next, &other) in
+                            map.neighbors4(tile)
+                        {
+                            if other == letter
+                                && boxed.insert(next)
+                            {
+                                queue.push_back(next);
+                            }
+                        }
+                    }
+                    boxes.push(Shape { tiles });
+                }
+                _ => {}
+            }
+        }
+
+        let occupied = boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, shape)| {
+                shape.tiles.iter().map(
+                    move |(position, _)| (*position, index),
+                )
+            })
+            .collect();
+        Ok(Warehouse {
+            size: IVec2::new(
+                map.width() as i32 * scale,
+                map.height() as i32,
+            ),
+            walls,
+            boxes,
+            occupied,
+            robot: robot.ok_or(MapError::NoRobot)?,
+        })
+    }
+
+    pub fn robot(&self) -> IVec2 {
+        self.robot
+    }
+
+    /// Boxes the robot pushes going in
+    /// `direction`, `None` when it is blocked.
+    pub fn pushed(
+        &self,
+        direction: Direction,
+    ) -> Option<Vec<usize>> {
+        let offset = direction.offset();
+        let mut visited = HashSet::new();
+        let mut pushed = vec![];
+        let mut to_check =
+            VecDeque::from([self.robot + offset]);
+
+        while let Some(position) = to_check.pop_front() {
+            if self.walls.contains(&position) {
+                return None;
+            }
+            let Some(&index) = self.occupied.get(&position)
+            else {
+                continue;
+            };
+            if !visited.insert(index) {
+                continue;
+            }
+            pushed.push(index);
+            to_check.extend(
+                self.boxes[index].tiles.iter().map(
+                    |(position, _)| *position + offset,
+                ),
+            );
+        }
+
+        Some(pushed)
+    }
+
+    /// Moves the boxes, all at once.
+    fn shift(&mut self, indices: &[usize], offset: IVec2) {
+        for &index in indices {
+            for (position, _) in &self.boxes[index].tiles {
+                self.occupied.remove(position);
+            }
+        }
+        for &index in indices {
+            for (position, _) in
+                &mut self.boxes[index].tiles
+            {
+                *position += offset;
+                self.occupied.insert(*position, index);
+            }
+        }
+    }
+
+    /// Moves the robot, returning the boxes it
+    /// pushed or `None` when it is blocked.
+    pub fn step(
+        &mut self,
+        direction: Direction,
+    ) -> Option<Vec<usize>> {
+        let pushed = self.pushed(direction)?;
+        self.shift(&pushed, direction.offset());
+        self.robot += direction.offset();
+        Some(pushed)
+    }
+
+    /// Takes back a step that pushed `pushed`.
+    pub fn unstep(
+        &mut self,
+        direction: Direction,
+        pushed: &[usize],
+    ) {
+        let offset = direction.opposite().offset();
+        self.shift(pushed, offset);
+        self.robot += offset;
+    }
+
+    /// Top left corner of each box, by index.
+    pub fn corners(
+        &self,
+    ) -> impl Iterator<Item = IVec2> + '_ {
+        self.boxes.iter().map(Shape::corner)
+    }
+
+    /// Sum of the GPS coordinates of the boxes,
+    /// from their top left corner.
+    pub fn gps(&self) -> i32 {
+        self.corners()
+            .map(|corner| 100 * corner.y + corner.x)
+            .sum()
+    }
+}
+
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let tiles = self
+            .boxes
+            .iter()
+            .flat_map(|shape| shape.tiles.iter().copied())
+            .collect::<HashMap<_, _>>();
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let position = IVec2::new(x, y);
+                let tile = if self.walls.contains(&position)
+                {
+                    '#'
+                } else if position == self.robot {
+                    '@'
+                } else {
+                    tiles
+                        .get(&position)
+                        .copied()
+                        .unwrap_or('.')
+                };
+                write!(f, "{tile}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_tile(ch: char) -> bool {
+    "#.@O[=]".contains(ch) || ch.is_alphabetic()
+}
+
+fn parse_input(
+    input: &str,
+) -> IResult<(Grid<char>, Vec<Direction>)> {
+    all_consuming(separated_pair(
+        grid(|ch| is_tile(ch).then_some(ch)),
+        pair(line_ending, line_ending),
+        terminated(
+            map(
+                separated_list0(
+                    line_ending,
+                    many0(Direction::parse),
+                ),
+                |directions| {
+                    directions
+                        .into_iter()
+                        .flatten()
+                        .collect()
+                },
+            ),
+            opt(line_ending),
+        ),
+    ))(input)
+}
+
+/// Warehouse and moves of a puzzle input, the map
+/// widened by `scale`.
+pub fn parse(
+    input: &str,
+    scale: i32,
+) -> miette::Result<(Warehouse, Vec<Direction>)> {
+    let (_, (map, directions)) = parse_input(input)
+        .map_err(|err| ParseError::new(input, err))?;
+    Ok((Warehouse::new(&map, scale)?, directions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn warehouse(map: &str, scale: i32) -> Warehouse {
+        Warehouse::new(&map.parse().unwrap(), scale)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scale() {
+        let map = "#####\n#@O.#\n#####";
+        assert_eq!(
+            map,
+            warehouse(map, 1).to_string().trim_end()
+        );
+        assert_eq!(
+            "##########\n##@.[]..##\n##########",
+            warehouse(map, 2).to_string().trim_end()
+        );
+        assert_eq!(
+            "###############\n###@..[=]...###\n###############",
+            warehouse(map, 3).to_string().trim_end()
+        );
+        // a drawn warehouse parses back to itself
+        let wide = warehouse(map, 3);
+        assert_eq!(wide, warehouse(&wide.to_string(), 1));
+    }
+
+    #[test]
+    fn test_push_shapes() {
+        // the L is pushed by its foot and pushes the
+        // box on top of its other arm
+        let mut warehouse = warehouse(
+            "#######
+#.....#
+#...O.#
+#.a.a.#
+#.aaa.#
+#..@..#
+#######",
+            1,
+        );
+        assert_eq!(
+            Some(vec![1, 0]),
+            warehouse.step(Direction::North)
+        );
+        assert_eq!(
+            "#######
+#...O.#
+#.a.a.#
+#.aaa.#
+#..@..#
+#.....#
+#######
+",
+            warehouse.to_string()
+        );
+        // the box then hits the wall
+        assert_eq!(None, warehouse.step(Direction::North));
+        warehouse.unstep(Direction::North, &[1, 0]);
+        assert_eq!(IVec2::new(3, 5), warehouse.robot());
+        // corners: a at (2, 3), O at (4, 2)
+        assert_eq!(302 + 204, warehouse.gps());
+    }
+
+    #[rstest]
+    #[case("#[#\n#@#", MapError::UnmatchedBracket { position: IVec2::new(1, 0), found: '[' })]
+    #[case("#]@", MapError::UnmatchedBracket { position: IVec2::new(1, 0), found: ']' })]
+    #[case("#.#", MapError::NoRobot)]
+    #[case("@.@", MapError::SeveralRobots { first: IVec2::new(0, 0), second: IVec2::new(2, 0) })]
+    fn test_invalid_map(
+        #[case] map: &str,
+        #[case] expected: MapError,
+    ) {
+        assert_eq!(
+            Err(expected),
+            Warehouse::new(&map.parse().unwrap(), 1)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use aoc_input::InputArgs;
+use clap::Parser;
+use day_16::{maze::Maze, Params};
+
+/// List the cheapest distinct routes through the
+/// maze, with how much more each costs than the
+/// best one.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: Params,
+    /// number of routes to list
+    #[clap(short, default_value_t = 3)]
+    k: usize,
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 1)?;
+    let maze = Maze::parse(&file)?;
+    let paths =
+        maze.best_paths(&args.params.costs, args.k)?;
+    let best = paths.first().map_or(0, |path| path.cost);
+    for (rank, path) in paths.iter().enumerate() {
+        println!(
+            "#{} cost {} (+{}), {} tiles",
+            rank + 1,
+            path.cost,
+            path.cost - best,
+            path.steps.len()
+        );
+    }
+    Ok(())
+}
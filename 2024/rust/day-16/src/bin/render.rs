@@ -0,0 +1,64 @@
+use aoc_input::InputArgs;
+use clap::Parser;
+use day_16::{maze::Maze, render::Overlay, Params};
+
+/// Draw a route over the maze, the other tiles on
+/// a best route marked `O`. With colors, the
+/// route's tiles another best route goes through
+/// are green.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(flatten)]
+    params: Params,
+    /// rank of the route to draw, 1 being the
+    /// cheapest, as listed by `paths`
+    #[clap(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    path: u32,
+    /// draw with terminal colors
+    #[clap(long)]
+    ansi: bool,
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 2)?;
+    let maze = Maze::parse(&file)?;
+    let costs = &args.params.costs;
+    let best = maze.best_routes(costs)?;
+    let rank = args.path as usize;
+    let Some(path) = maze
+        .best_paths(costs, rank)?
+        .into_iter()
+        .nth(rank - 1)
+    else {
+        miette::bail!(
+            "the maze has fewer than {rank} routes"
+        );
+    };
+
+    let overlay = Overlay::new(&maze, &best, &path);
+    if args.ansi {
+        print!("{}", overlay.to_ansi());
+    } else {
+        print!("{overlay}");
+    }
+    println!(
+        "route {rank} costs {} (best {}), {} tiles on \
+         best routes",
+        path.cost,
+        best.cost,
+        best.counts.len()
+    );
+    Ok(())
+}
@@ -1,2 +1,16 @@
+use maze::MazeCosts;
+
+pub mod maze;
 pub mod part1;
 pub mod part2;
+pub mod render;
+
+/// Puzzle parameters, the defaults are the real
+/// puzzle's.
+#[derive(
+    clap::Args, Debug, Clone, PartialEq, Eq, Default,
+)]
+pub struct Params {
+    #[clap(flatten)]
+    pub costs: MazeCosts,
+}
@@ -0,0 +1,658 @@
+//! The reindeer maze with configurable costs,
+//! shared by both parts.
+//!
+//! A state is a tile with the heading the
+//! reindeer arrived on it with. A move turns the
+//! reindeer if needed then steps forward, so a
+//! path of states is a route of tiles: distinct
+//! paths are distinct routes, not the same route
+//! turning in another order.
+//!
+//! Costs are given as `u32` but added up as
+//! `u64`, so no route through a maze that fits in
+//! memory overflows whatever the costs.
+use aoc_grid::{parser::grid, Grid};
+use aoc_parse::{IResult, ParseError};
+use glam::IVec2;
+use miette::Diagnostic;
+use nom::{
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    sequence::terminated,
+};
+use pathfinding::prelude::{
+    astar, astar_bag, dijkstra_all, yen,
+};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(
+    clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash,
+)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn offset(self) -> IVec2 {
+        match self {
+            Direction::North => IVec2::NEG_Y,
+            Direction::East => IVec2::X,
+            Direction::South => IVec2::Y,
+            Direction::West => IVec2::NEG_X,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+
+    /// Quarter turns from `self` to `other`,
+    /// either way.
+    fn quarter_turns(self, other: Direction) -> u64 {
+        let index = |direction| {
+            Self::ALL
+                .iter()
+                .position(|d| *d == direction)
+                .unwrap() as u64
+        };
+        match (index(other) + 4 - index(self)) % 4 {
+            3 => 1,
+            turns => turns,
+        }
+    }
+}
+
+/// How much the reindeer pays to move, the
+/// defaults are the puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct MazeCosts {
+    /// cost of a step forward, at least 1
+    #[clap(
+        long = "step-cost",
+        default_value_t = MazeCosts::default().step,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub step: u32,
+    /// cost of a quarter turn
+    #[clap(
+        long = "turn-cost",
+        default_value_t = MazeCosts::default().turn
+    )]
+    pub turn: u32,
+    /// cost of turning around at once, two
+    /// quarter turns otherwise
+    #[clap(long = "reverse-cost")]
+    pub reverse: Option<u32>,
+    /// heading of the reindeer on the start tile
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = MazeCosts::default().start_heading
+    )]
+    pub start_heading: Direction,
+    /// heading the reindeer must end with, any
+    /// when not given
+    #[clap(long, value_enum)]
+    pub end_heading: Option<Direction>,
+}
+
+impl Default for MazeCosts {
+    fn default() -> Self {
+        MazeCosts {
+            step: 1,
+            turn: 1000,
+            reverse: None,
+            start_heading: Direction::East,
+            end_heading: None,
+        }
+    }
+}
+
+impl MazeCosts {
+    /// Cheapest way to turn from `from` to `to`.
+    pub fn turn(
+        &self,
+        from: Direction,
+        to: Direction,
+    ) -> u64 {
+        let quarters = from.quarter_turns(to);
+        let turns = u64::from(self.turn) * quarters;
+        match (quarters, self.reverse) {
+            (2, Some(reverse)) => {
+                u64::from(reverse).min(turns)
+            }
+            _ => turns,
+        }
+    }
+}
+
+#[derive(Debug, Error, Diagnostic, PartialEq, Eq)]
+pub enum MazeError {
+    #[error("the maze has no start `S`")]
+    #[diagnostic(code(day_16::no_start))]
+    NoStart,
+    #[error("the maze has no end `E`")]
+    #[diagnostic(code(day_16::no_end))]
+    NoEnd,
+    #[error("the reindeer cannot reach the end")]
+    #[diagnostic(
+        code(day_16::no_path),
+        help("the end is walled off from the start")
+    )]
+    NoPath,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct State {
+    position: IVec2,
+    heading: Direction,
+    /// turned to the end heading on the end tile
+    finished: bool,
+}
+
+/// A route through the maze.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    pub cost: u64,
+    /// tiles from the start to the end, with the
+    /// heading the reindeer arrived with
+    pub steps: Vec<(IVec2, Direction)>,
+}
+
+impl Path {
+    pub fn tiles(
+        &self,
+    ) -> impl Iterator<Item = IVec2> + '_ {
+        self.steps.iter().map(|(position, _)| *position)
+    }
+}
+
+/// The cheapest routes through the maze.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestRoutes {
+    pub cost: u64,
+    /// tiles on any of the routes, with how many
+    /// of them go through
+    pub counts: HashMap<IVec2, u64>,
+}
+
+pub struct Maze {
+    walls: Grid<bool>,
+    start: IVec2,
+    end: IVec2,
+}
+
+fn parse_grid(input: &str) -> IResult<Grid<char>> {
+    all_consuming(terminated(
+        grid(|ch| "#.SE".contains(ch).then_some(ch)),
+        opt(line_ending),
+    ))(input)
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> miette::Result<Self> {
+        let (_, map) = parse_grid(input)
+            .map_err(|err| ParseError::new(input, err))?;
+        Ok(Maze {
+            start: map
+                .find('S')
+                .ok_or(MazeError::NoStart)?,
+            end: map.find('E').ok_or(MazeError::NoEnd)?,
+            walls: map.map(|tile| *tile == '#'),
+        })
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.walls.size()
+    }
+
+    pub fn start(&self) -> IVec2 {
+        self.start
+    }
+
+    pub fn end(&self) -> IVec2 {
+        self.end
+    }
+
+    /// Whether `position` is a wall, the outside
+    /// being one.
+    pub fn is_wall(&self, position: IVec2) -> bool {
+        self.walls.get(position).copied().unwrap_or(true)
+    }
+
+    fn initial(&self, costs: &MazeCosts) -> State {
+        State {
+            position: self.start,
+            heading: costs.start_heading,
+            finished: false,
+        }
+    }
+
+    fn successors(
+        &self,
+        state: &State,
+        costs: &MazeCosts,
+    ) -> Vec<(State, u64)> {
+        if state.finished {
+            return vec![];
+        }
+        let mut successors = Direction::ALL
+            .iter()
+            .map(|&heading| State {
+                position: state.position + heading.offset(),
+                heading,
+                finished: false,
+            })
+            .filter(|next| !self.is_wall(next.position))
+            .map(|next| {
+                let cost = costs
+                    .turn(state.heading, next.heading)
+                    + u64::from(costs.step);
+                (next, cost)
+            })
+            .collect::<Vec<_>>();
+        if let Some(heading) = costs.end_heading {
+            if state.position == self.end {
+                successors.push((
+                    State {
+                        position: self.end,
+                        heading,
+                        finished: true,
+                    },
+                    costs.turn(state.heading, heading),
+                ));
+            }
+        }
+        successors
+    }
+
+    fn success(
+        &self,
+        state: &State,
+        costs: &MazeCosts,
+    ) -> bool {
+        state.position == self.end
+            && (state.finished
+                || costs.end_heading.is_none())
+    }
+
+    fn heuristic(
+        &self,
+        state: &State,
+        costs: &MazeCosts,
+    ) -> u64 {
+        let distance = (self.end - state.position).abs();
+        ((distance.x + distance.y) as u64)
+            .saturating_mul(u64::from(costs.step))
+    }
+
+    /// Cost of the cheapest route to the end.
+    pub fn cheapest(
+        &self,
+        costs: &MazeCosts,
+    ) -> Result<u64, MazeError> {
+        astar(
+            &self.initial(costs),
+            |state| self.successors(state, costs),
+            |state| self.heuristic(state, costs),
+            |state| self.success(state, costs),
+        )
+        .map(|(_, cost)| cost)
+        .ok_or(MazeError::NoPath)
+    }
+
+    /// Tiles on any of the cheapest routes, with
+    /// their cost.
+    pub fn best_tiles(
+        &self,
+        costs: &MazeCosts,
+    ) -> Result<(HashSet<IVec2>, u64), MazeError> {
+        let (solutions, cost) = astar_bag(
+            &self.initial(costs),
+            |state| self.successors(state, costs),
+            |state| self.heuristic(state, costs),
+            |state| self.success(state, costs),
+        )
+        .ok_or(MazeError::NoPath)?;
+        let tiles = solutions
+            .flatten()
+            .map(|state| state.position)
+            .collect();
+        Ok((tiles, cost))
+    }
+
+    /// How many cheapest routes go through each
+    /// tile, counted over the states on them
+    /// rather than listed, there can be a lot.
+    /// Counts saturate, and need steps costing
+    /// more than nothing.
+    pub fn best_routes(
+        &self,
+        costs: &MazeCosts,
+    ) -> Result<BestRoutes, MazeError> {
+        let initial = self.initial(costs);
+        let mut distances =
+            dijkstra_all(&initial, |state| {
+                self.successors(state, costs)
+            })
+            .into_iter()
+            .map(|(state, (_, cost))| (state, cost))
+            .collect::<HashMap<_, _>>();
+        distances.insert(initial.clone(), 0);
+        let cost = distances
+            .iter()
+            .filter(|(state, _)| self.success(state, costs))
+            .map(|(_, cost)| *cost)
+            .min()
+            .ok_or(MazeError::NoPath)?;
+
+        // only turning to the end heading is free,
+        // and it finishes
+        let mut states =
+            distances.keys().cloned().collect::<Vec<_>>();
+        states.sort_by_key(|state| {
+            (distances[state], state.finished)
+        });
+        // moves on a cheapest route to their state
+        let distances = &distances;
+        let tight = |state: &State| {
+            let distance = distances[state];
+            self.successors(state, costs)
+                .into_iter()
+                .filter(move |(next, step)| {
+                    distance.checked_add(*step)
+                        == Some(distances[next])
+                })
+        };
+
+        // routes from the start to each state
+        let mut from_start = HashMap::from([(initial, 1)]);
+        for state in &states {
+            let routes =
+                from_start.get(state).copied().unwrap_or(0);
+            if routes == 0 || self.success(state, costs) {
+                continue;
+            }
+            for (next, _) in tight(state) {
+                let count =
+                    from_start.entry(next).or_insert(0u64);
+                *count = count.saturating_add(routes);
+            }
+        }
+        // routes from each state to the end
+        let mut to_end = HashMap::new();
+        for state in states.iter().rev() {
+            let routes = if self.success(state, costs) {
+                u64::from(distances[state] == cost)
+            } else {
+                tight(state)
+                    .map(|(next, _)| {
+                        to_end
+                            .get(&next)
+                            .copied()
+                            .unwrap_or(0)
+                    })
+                    .fold(0u64, u64::saturating_add)
+            };
+            to_end.insert(state.clone(), routes);
+        }
+
+        let mut counts = HashMap::new();
+        for state in &states {
+            let routes = from_start
+                .get(state)
+                .copied()
+                .unwrap_or(0)
+                .saturating_mul(to_end[state]);
+            // the end tile is counted before turning
+            // to the end heading
+            if routes > 0 && !state.finished {
+                let count = counts
+                    .entry(state.position)
+                    .or_insert(0);
+                *count = routes.saturating_add(*count);
+            }
+        }
+        Ok(BestRoutes { cost, counts })
+    }
+
+    /// The `k` cheapest distinct routes, cheapest
+    /// first, fewer when there are not that many.
+    pub fn best_paths(
+        &self,
+        costs: &MazeCosts,
+        k: usize,
+    ) -> Result<Vec<Path>, MazeError> {
+        if k == 0 {
+            return Ok(vec![]);
+        }
+        let paths = yen(
+            &self.initial(costs),
+            |state| self.successors(state, costs),
+            |state| self.success(state, costs),
+            k,
+        )
+        .into_iter()
+        .map(|(states, cost)| Path {
+            cost,
+            steps: states
+                .into_iter()
+                .filter(|state| !state.finished)
+                .map(|state| {
+                    (state.position, state.heading)
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return Err(MazeError::NoPath);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    #[rstest]
+    #[case(MazeCosts::default(), 2)]
+    #[case(MazeCosts { start_heading: Direction::West, ..Default::default() }, 2002)]
+    #[case(MazeCosts { start_heading: Direction::West, reverse: Some(500), ..Default::default() }, 502)]
+    #[case(MazeCosts { start_heading: Direction::North, ..Default::default() }, 1002)]
+    #[case(MazeCosts { end_heading: Some(Direction::North), ..Default::default() }, 1002)]
+    #[case(MazeCosts { end_heading: Some(Direction::West), reverse: Some(3000), ..Default::default() }, 2002)]
+    #[case(MazeCosts { step: 7, turn: 0, end_heading: Some(Direction::West), ..Default::default() }, 14)]
+    #[case(MazeCosts { turn: 1 << 31, end_heading: Some(Direction::West), reverse: Some(3), ..Default::default() }, 5)]
+    fn test_costs(
+        #[case] costs: MazeCosts,
+        #[case] expected: u64,
+    ) {
+        let maze =
+            Maze::parse("#####\n#S.E#\n#####").unwrap();
+        assert_eq!(Ok(expected), maze.cheapest(&costs));
+    }
+
+    // the fewest steps take 28 with 10 turns, the
+    // fewest turns 7 with 36 steps
+    #[rstest]
+    #[case(MazeCosts { step: 1_000_000_000, ..Default::default() }, 28 * 1_000_000_000 + 10 * 1000)]
+    #[case(MazeCosts { turn: 4_000_000_000, ..Default::default() }, 7 * 4_000_000_000 + 36)]
+    #[case(MazeCosts { step: u32::MAX, turn: u32::MAX, ..Default::default() }, 38 * u64::from(u32::MAX))]
+    fn test_huge_costs(
+        #[case] costs: MazeCosts,
+        #[case] expected: u64,
+    ) {
+        let maze = Maze::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(expected), maze.cheapest(&costs));
+        let paths = maze.best_paths(&costs, 3).unwrap();
+        assert_eq!(expected, paths[0].cost);
+        let best = maze.best_routes(&costs).unwrap();
+        assert_eq!(expected, best.cost);
+        let (_, cost) = maze.best_tiles(&costs).unwrap();
+        assert_eq!(expected, cost);
+    }
+
+    #[test]
+    fn test_turn_widens() {
+        let costs = MazeCosts {
+            turn: u32::MAX,
+            ..Default::default()
+        };
+        assert_eq!(
+            2 * u64::from(u32::MAX),
+            costs.turn(Direction::East, Direction::West)
+        );
+        assert_eq!(
+            0,
+            costs.turn(Direction::East, Direction::East)
+        );
+    }
+
+    #[test]
+    fn test_best_paths() {
+        let maze = Maze::parse(EXAMPLE).unwrap();
+        let costs = MazeCosts::default();
+        let paths = maze.best_paths(&costs, 5).unwrap();
+        assert_eq!(5, paths.len());
+        // the three best routes of the puzzle
+        let totals = paths
+            .iter()
+            .map(|path| path.cost)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![7036, 7036, 7036], totals[..3]);
+        assert!(totals[3] > 7036);
+        assert!(totals.is_sorted());
+
+        let routes = paths
+            .iter()
+            .map(|path| path.tiles().collect::<Vec<_>>())
+            .collect::<HashSet<_>>();
+        assert_eq!(5, routes.len());
+        let (tiles, _) = maze.best_tiles(&costs).unwrap();
+        assert_eq!(
+            tiles,
+            paths[..3]
+                .iter()
+                .flat_map(Path::tiles)
+                .collect()
+        );
+        for path in &paths {
+            assert_eq!(
+                Some(maze.start()),
+                path.tiles().next()
+            );
+            assert_eq!(
+                Some(maze.end()),
+                path.tiles().last()
+            );
+        }
+        assert_eq!(Ok(vec![]), maze.best_paths(&costs, 0));
+    }
+
+    #[rstest]
+    #[case(MazeCosts::default())]
+    #[case(MazeCosts { end_heading: Some(Direction::South), ..Default::default() })]
+    #[case(MazeCosts { start_heading: Direction::North, turn: 1, ..Default::default() })]
+    fn test_best_routes(#[case] costs: MazeCosts) {
+        let maze = Maze::parse(EXAMPLE).unwrap();
+        let best = maze.best_routes(&costs).unwrap();
+        let (tiles, cost) =
+            maze.best_tiles(&costs).unwrap();
+        assert_eq!(cost, best.cost);
+        assert_eq!(
+            tiles,
+            best.counts.keys().copied().collect()
+        );
+
+        // every route goes through the start and
+        // the end, listed they are the same
+        let paths = maze
+            .best_paths(&costs, 20)
+            .unwrap()
+            .into_iter()
+            .filter(|path| path.cost == cost)
+            .collect::<Vec<_>>();
+        assert!(paths.len() < 20);
+        assert_eq!(
+            paths.len() as u64,
+            best.counts[&maze.start()]
+        );
+        assert_eq!(
+            paths.len() as u64,
+            best.counts[&maze.end()]
+        );
+        for (tile, count) in &best.counts {
+            let through = paths
+                .iter()
+                .filter(|path| {
+                    path.tiles().any(|t| t == *tile)
+                })
+                .count();
+            assert_eq!(through as u64, *count, "{tile}");
+        }
+    }
+
+    #[rstest]
+    #[case("#####\n#S..#\n#####", MazeError::NoEnd)]
+    #[case("#####\n#..E#\n#####", MazeError::NoStart)]
+    fn test_invalid(
+        #[case] input: &str,
+        #[case] expected: MazeError,
+    ) {
+        let err = Maze::parse(input).err().unwrap();
+        assert_eq!(Some(&expected), err.downcast_ref());
+    }
+
+    #[test]
+    fn test_no_path() {
+        let maze =
+            Maze::parse("#####\n#S#E#\n#####").unwrap();
+        let costs = MazeCosts::default();
+        assert_eq!(
+            Err(MazeError::NoPath),
+            maze.cheapest(&costs)
+        );
+        assert_eq!(
+            Err(MazeError::NoPath),
+            maze.best_paths(&costs, 3)
+        );
+        assert_eq!(
+            Err(MazeError::NoPath),
+            maze.best_routes(&costs)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! The maze with a route drawn on it, for
+//! checking which tiles are counted as on a best
+//! route.
+//!
+//! The chosen route is drawn with the heading the
+//! reindeer leaves each tile with, the other
+//! tiles on any best route with `O`. Best routes
+//! share tiles around junctions, where the
+//! route's arrows hide the `O`s: with colors
+//! those arrows are green like them rather than
+//! yellow.
+use crate::maze::{BestRoutes, Maze, Path};
+use aoc_grid::Grid;
+use glam::IVec2;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Tiles of the maze with a route overlaid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlay {
+    tiles: Grid<char>,
+    /// tiles of the route on another best route
+    shared: Grid<bool>,
+}
+
+impl Overlay {
+    /// Draws `path` over the maze and the `best`
+    /// routes.
+    pub fn new(
+        maze: &Maze,
+        best: &BestRoutes,
+        path: &Path,
+    ) -> Self {
+        let mut tiles = Grid::filled(maze.size(), '.');
+        for y in 0..maze.size().y {
+            for x in 0..maze.size().x {
+                let position = IVec2::new(x, y);
+                if maze.is_wall(position) {
+                    tiles[position] = '#';
+                } else if best
+                    .counts
+                    .contains_key(&position)
+                {
+                    tiles[position] = 'O';
+                }
+            }
+        }
+        // the route is one of the best routes
+        // counted, or none of them
+        let own = u64::from(path.cost == best.cost);
+        let mut shared = Grid::filled(maze.size(), false);
+        for position in path.tiles() {
+            shared[position] = best
+                .counts
+                .get(&position)
+                .is_some_and(|count| *count > own);
+        }
+        for pair in path.steps.windows(2) {
+            let (position, _) = pair[0];
+            let (_, leaving) = pair[1];
+            tiles[position] = leaving.to_char();
+        }
+        tiles[maze.start()] = 'S';
+        tiles[maze.end()] = 'E';
+        Overlay { tiles, shared }
+    }
+
+    pub fn tile(&self, position: IVec2) -> char {
+        self.tiles[position]
+    }
+
+    /// Whether the route goes through `position`
+    /// along with another best route.
+    pub fn is_shared(&self, position: IVec2) -> bool {
+        self.shared[position]
+    }
+
+    /// The overlay with colors: the route in bold
+    /// yellow, bold green where another best
+    /// route goes too, the other best tiles
+    /// in green and the walls dimmed.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        let width = self.tiles.width();
+        for (row, shared) in self
+            .tiles
+            .cells()
+            .chunks(width)
+            .zip(self.shared.cells().chunks(width))
+        {
+            // only switch colors between runs
+            let mut previous = None;
+            for (&tile, &shared) in row.iter().zip(shared) {
+                let color = match tile {
+                    '#' => "2",
+                    'O' => "32",
+                    '^' | '>' | 'v' | '<' if shared => {
+                        "1;32"
+                    }
+                    '^' | '>' | 'v' | '<' => "1;33",
+                    'S' | 'E' => "1;31",
+                    _ => "",
+                };
+                if previous != Some(color) {
+                    write!(text, "\x1b[0;{color}m")
+                        .unwrap();
+                    previous = Some(color);
+                }
+                text.push(tile);
+            }
+            text.push_str("\x1b[0m\n");
+        }
+        text
+    }
+}
+
+impl Display for Overlay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::MazeCosts;
+
+    const MAZE: &str = "#######
+#.....#
+#S###E#
+#.....#
+#######";
+
+    fn overlay(rank: usize) -> Overlay {
+        let maze = Maze::parse(MAZE).unwrap();
+        let costs = MazeCosts::default();
+        let best = maze.best_routes(&costs).unwrap();
+        let paths =
+            maze.best_paths(&costs, rank + 1).unwrap();
+        Overlay::new(&maze, &best, &paths[rank])
+    }
+
+    #[test]
+    fn test_overlay() {
+        // the two best routes go around the wall,
+        // each drawn over the other
+        let drawn = [overlay(0), overlay(1)]
+            .map(|overlay| overlay.to_string());
+        let expected = [
+            "#######
+#>>>>v#
+#S###E#
+#OOOOO#
+#######
+",
+            "#######
+#OOOOO#
+#S###E#
+#>>>>^#
+#######
+",
+        ];
+        assert!(
+            drawn == expected
+                || drawn == [expected[1], expected[0]],
+            "{drawn:?}"
+        );
+    }
+
+    #[test]
+    fn test_ansi() {
+        let overlay = overlay(0);
+        let ansi = overlay.to_ansi();
+        assert_eq!(5, ansi.lines().count());
+        assert!(ansi.contains("\x1b[0;1;31mS"));
+        assert!(ansi.contains("\x1b[0;32mOOOOO"));
+        assert!(ansi.contains("\x1b[0;1;33m>>>>"));
+        assert!(ansi.ends_with("\x1b[0;2m#######\x1b[0m\n"));
+        assert_eq!('E', overlay.tile(IVec2::new(5, 2)));
+    }
+
+    #[test]
+    fn test_shared() {
+        // the best routes split around the middle
+        // wall, sharing the corridors on its sides
+        let maze = Maze::parse(
+            "###########
+###.....###
+#S..###..E#
+###.....###
+###########",
+        )
+        .unwrap();
+        let costs = MazeCosts::default();
+        let best = maze.best_routes(&costs).unwrap();
+        let paths = maze.best_paths(&costs, 3).unwrap();
+        assert_eq!(best.cost, paths[1].cost);
+        assert!(paths[2].cost > best.cost);
+
+        let overlay = Overlay::new(&maze, &best, &paths[0]);
+        let shared = (0..11)
+            .map(|x| IVec2::new(x, 2))
+            .filter(|position| overlay.is_shared(*position))
+            .map(|position| position.x)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 2, 3, 7, 8, 9], shared);
+        for x in 3..=7 {
+            assert!(!overlay.is_shared(IVec2::new(x, 1)));
+            assert!(!overlay.is_shared(IVec2::new(x, 3)));
+        }
+        let ansi = overlay.to_ansi();
+        assert!(
+            ansi.contains("\x1b[0;1;32m>"),
+            "{ansi:?}"
+        );
+        assert!(
+            ansi.contains("\x1b[0;1;33m"),
+            "{ansi:?}"
+        );
+
+        // a route off the best ones shares them all
+        let overlay = Overlay::new(&maze, &best, &paths[2]);
+        for position in paths[2].tiles() {
+            assert_eq!(
+                best.counts.contains_key(&position),
+                overlay.is_shared(position)
+            );
+        }
+    }
+}
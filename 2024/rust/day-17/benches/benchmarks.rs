@@ -6,17 +6,91 @@ fn main() {
 }
 
 #[divan::bench]
-fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+fn part1(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        1,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part1::process(divan::black_box(&input)).unwrap()
+    });
 }
 
 #[divan::bench]
-fn part2() {
-    part2::process(divan::black_box(include_str!(
-        "../input2.txt",
-    )))
-    .unwrap();
+fn part2(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        2,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part2::process(divan::black_box(&input)).unwrap()
+    });
+}
+
+/// Computer running one of the programs of the
+/// interpreter and compiled benchmarks, the real
+/// input's when present.
+fn computer(name: &str) -> Option<computer::Computer> {
+    let program = match name {
+        // adv 3; out A; jnz 0
+        "example" => vec![0, 3, 5, 4, 3, 0],
+        // bst A; bxl 5; cdv B; bxc; out B; adv 2; jnz 0
+        "shift 2" => {
+            vec![2, 4, 1, 5, 7, 5, 4, 0, 5, 5, 0, 2, 3, 0]
+        }
+        _ => {
+            let input = aoc_input::bench_input(
+                env!("CARGO_MANIFEST_DIR"),
+                2,
+            )?;
+            let (_, computer) = computer::parse(&input)
+                .expect("valid input");
+            return Some(computer);
+        }
+    };
+    Some(computer::Computer::new([0; 3], program))
+}
+
+/// Register A values tried per iteration.
+const CANDIDATES: u64 = 1 << 12;
+
+#[divan::bench(args = ["example", "shift 2", "input"])]
+fn interpreter(bencher: divan::Bencher, name: &str) {
+    let Some(computer) = computer(name) else {
+        return;
+    };
+    bencher.bench(|| {
+        (0..CANDIDATES)
+            .map(|a| {
+                let mut computer = computer.clone();
+                computer
+                    .set_register_a(divan::black_box(a));
+                computer.outputs().count()
+            })
+            .sum::<usize>()
+    });
+}
+
+#[divan::bench(args = ["example", "shift 2", "input"])]
+fn compiled(bencher: divan::Bencher, name: &str) {
+    let Some(computer) = computer(name) else {
+        return;
+    };
+    let compiled = compiled::Compiled::new(&computer);
+    bencher.bench(|| {
+        (0..CANDIDATES)
+            .map(|a| {
+                compiled
+                    .run(computer::Registers {
+                        a: divan::black_box(a),
+                        ..computer.registers()
+                    })
+                    .count()
+            })
+            .sum::<usize>()
+    });
 }
@@ -0,0 +1,91 @@
+use aoc_input::InputArgs;
+use clap::{Parser, Subcommand};
+use day_17::debug::{
+    disassemble, Breakpoint, Debugger, Stop,
+};
+use itertools::Itertools;
+
+/// Inspect the program of the day's 3-bit
+/// computer.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    #[clap(flatten)]
+    input: InputArgs,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the program as a mnemonic listing.
+    Disasm,
+    /// Run the program printing every executed
+    /// instruction, stopping at the first
+    /// breakpoint that holds.
+    Trace {
+        /// stop before an instruction when the
+        /// condition holds, e.g. `ip=6` or `A==0`
+        #[clap(short, long = "break")]
+        breakpoints: Vec<Breakpoint>,
+        /// start with this value in register A
+        /// instead of the input's
+        #[clap(short = 'a', long)]
+        register_a: Option<u64>,
+        /// maximum number of instructions to run
+        #[clap(long, default_value_t = 10_000)]
+        limit: usize,
+    },
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let file =
+        args.input.read(env!("CARGO_MANIFEST_DIR"), 1)?;
+    let mut debugger = Debugger::new(&file)?;
+
+    match args.command {
+        Command::Disasm => {
+            print!("{}", disassemble(debugger.program()));
+        }
+        Command::Trace {
+            breakpoints,
+            register_a,
+            limit,
+        } => {
+            if let Some(a) = register_a {
+                debugger.set_register_a(a);
+            }
+            for breakpoint in breakpoints {
+                debugger.add_breakpoint(breakpoint);
+            }
+            println!("    {}", debugger.registers());
+
+            let stop = debugger.run(limit);
+            for step in debugger.steps() {
+                println!("{step}");
+            }
+            match stop {
+                Stop::Halted => println!("halted"),
+                Stop::Breakpoint(breakpoint) => println!(
+                    "breakpoint {breakpoint} at ip {}",
+                    debugger.ip()
+                ),
+                Stop::Limit => {
+                    println!("stopped after {limit} steps")
+                }
+                Stop::Fault(err) => {
+                    println!("fault: {err}")
+                }
+            }
+            println!(
+                "output: {}",
+                debugger.output().iter().join(",")
+            );
+        }
+    }
+    Ok(())
+}
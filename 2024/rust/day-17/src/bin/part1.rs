@@ -1,12 +1,16 @@
-use day_17::part1::process;
+use day_17::{part1::process_with, Params};
 use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
-    let file = include_str!("../../input1.txt");
-    let result = process(file).context("process part 1")?;
+    let (file, params) = aoc_input::load_with::<Params>(
+        env!("CARGO_MANIFEST_DIR"),
+        1,
+    )?;
+    let result = process_with(&file, &params)
+        .context("process part 1")?;
     println!("{}", result);
     Ok(())
 }
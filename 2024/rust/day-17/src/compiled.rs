@@ -0,0 +1,266 @@
+//! Programs decoded once into instructions with
+//! their operands resolved, for running the same
+//! program with many values of register A.
+//!
+//! Running a [`Compiled`] program only copies the
+//! registers, nothing is allocated and no opcode
+//! is decoded while it runs.
+use crate::computer::{Computer, Registers, VmError};
+
+/// Combo operand, resolved to a literal or a
+/// register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Literal(u64),
+    A,
+    B,
+    C,
+}
+
+impl Operand {
+    fn decode(
+        ip: usize,
+        operand: u8,
+    ) -> Result<Self, VmError> {
+        match operand {
+            0..=3 => Ok(Operand::Literal(operand as u64)),
+            4 => Ok(Operand::A),
+            5 => Ok(Operand::B),
+            6 => Ok(Operand::C),
+            _ => {
+                Err(VmError::InvalidOperand { ip, operand })
+            }
+        }
+    }
+
+    fn value(self, registers: &Registers) -> u64 {
+        match self {
+            Operand::Literal(value) => value,
+            Operand::A => registers.a,
+            Operand::B => registers.b,
+            Operand::C => registers.c,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Adv(Operand),
+    Bxl(u64),
+    Bst(Operand),
+    Jnz(usize),
+    Bxc,
+    Out(Operand),
+    Bdv(Operand),
+    Cdv(Operand),
+    /// the bytes at this address do not decode,
+    /// failing only if it is executed
+    Fault(VmError),
+}
+
+impl Instruction {
+    fn decode(ip: usize, opcode: u8, operand: u8) -> Self {
+        let combo = || Operand::decode(ip, operand);
+        let instruction = match opcode {
+            0 => combo().map(Instruction::Adv),
+            1 => Ok(Instruction::Bxl(operand as u64)),
+            2 => combo().map(Instruction::Bst),
+            3 => Ok(Instruction::Jnz(operand as usize)),
+            4 => Ok(Instruction::Bxc),
+            5 => combo().map(Instruction::Out),
+            6 => combo().map(Instruction::Bdv),
+            7 => combo().map(Instruction::Cdv),
+            opcode => {
+                Err(VmError::InvalidOpcode { ip, opcode })
+            }
+        };
+        instruction.unwrap_or_else(Instruction::Fault)
+    }
+}
+
+/// A program decoded at every address, so jumps
+/// to odd addresses behave like in the
+/// interpreter.
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    instructions: Vec<Instruction>,
+    step_limit: usize,
+}
+
+impl Compiled {
+    /// Decodes the program of `computer`, keeping
+    /// its step limit.
+    pub fn new(computer: &Computer) -> Self {
+        let program = &computer.program;
+        let instructions = program
+            .windows(2)
+            .enumerate()
+            .map(|(ip, window)| {
+                Instruction::decode(
+                    ip, window[0], window[1],
+                )
+            })
+            .collect();
+        Compiled {
+            instructions,
+            step_limit: computer.step_limit,
+        }
+    }
+
+    /// Runs the program from the first
+    /// instruction.
+    pub fn run(&self, registers: Registers) -> Run<'_> {
+        Run {
+            compiled: self,
+            registers,
+            ip: 0,
+            steps: 0,
+            faulted: false,
+        }
+    }
+}
+
+/// Values output by a running [`Compiled`]
+/// program, ending after the first error.
+#[derive(Debug, Clone)]
+pub struct Run<'a> {
+    compiled: &'a Compiled,
+    registers: Registers,
+    ip: usize,
+    steps: usize,
+    faulted: bool,
+}
+
+/// A divided by 2 to the power of the operand,
+/// shared by adv, bdv and cdv.
+fn divide(
+    registers: &Registers,
+    ip: usize,
+    operand: Operand,
+) -> Result<u64, VmError> {
+    let shift = operand.value(registers);
+    u32::try_from(shift)
+        .ok()
+        .and_then(|shift| registers.a.checked_shr(shift))
+        .ok_or(VmError::ShiftOverflow { ip, shift })
+}
+
+impl Run<'_> {
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    /// Executes instructions up to the next
+    /// output.
+    fn resume(&mut self) -> Result<Option<u8>, VmError> {
+        let compiled = self.compiled;
+        while let Some(instruction) =
+            compiled.instructions.get(self.ip)
+        {
+            let ip = self.ip;
+            if self.steps >= compiled.step_limit {
+                return Err(VmError::StepLimitExceeded {
+                    ip,
+                    limit: compiled.step_limit,
+                });
+            }
+            self.steps += 1;
+
+            let registers = &mut self.registers;
+            let mut out = None;
+            match *instruction {
+                Instruction::Adv(operand) => {
+                    registers.a =
+                        divide(registers, ip, operand)?;
+                }
+                Instruction::Bxl(literal) => {
+                    registers.b ^= literal;
+                }
+                Instruction::Bst(operand) => {
+                    registers.b =
+                        operand.value(registers) % 8;
+                }
+                Instruction::Jnz(target) => {
+                    if registers.a != 0 {
+                        self.ip = target;
+                        continue;
+                    }
+                }
+                Instruction::Bxc => {
+                    registers.b ^= registers.c;
+                }
+                Instruction::Out(operand) => {
+                    out = Some(
+                        (operand.value(registers) % 8)
+                            as u8,
+                    );
+                }
+                Instruction::Bdv(operand) => {
+                    registers.b =
+                        divide(registers, ip, operand)?;
+                }
+                Instruction::Cdv(operand) => {
+                    registers.c =
+                        divide(registers, ip, operand)?;
+                }
+                Instruction::Fault(err) => return Err(err),
+            }
+            self.ip += 2;
+            if out.is_some() {
+                return Ok(out);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Iterator for Run<'_> {
+    type Item = Result<u8, VmError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.faulted {
+            return None;
+        }
+        let result = self.resume();
+        self.faulted = result.is_err();
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[0, 1, 5, 4, 3, 0])]
+    #[case(&[2, 4, 1, 5, 7, 5, 4, 0, 5, 5, 0, 2, 3, 0])]
+    #[case(&[0, 1, 5, 4, 3, 1])]
+    #[case(&[5, 4, 8, 0])]
+    #[case(&[5, 4, 5, 7])]
+    #[case(&[6, 4])]
+    #[case(&[5, 4, 3, 0])]
+    fn test_same_as_interpreter(#[case] program: &[u8]) {
+        for a in [0, 7, 100, 2925] {
+            let mut computer =
+                Computer::new([a, 0, 0], program.to_vec());
+            computer.step_limit = 100;
+            let compiled = Compiled::new(&computer);
+            let mut run =
+                compiled.run(computer.registers());
+
+            let expected = computer
+                .by_ref()
+                .filter_map(Result::transpose)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                expected,
+                run.by_ref().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                computer.registers(),
+                run.registers()
+            );
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! The 3-bit computer shared by both parts.
+use aoc_parse::IResult;
+use miette::Diagnostic;
+use nom::{
+    bytes::complete::tag,
+    character::{
+        complete,
+        complete::{anychar, line_ending},
+    },
+    combinator::{cut, opt},
+    error::context,
+    multi::{many_m_n, separated_list1},
+    sequence::{delimited, separated_pair},
+};
+use std::fmt;
+use thiserror::Error;
+
+/// Instructions executed before giving up on a
+/// program that does not halt.
+pub const DEFAULT_STEP_LIMIT: usize = 100_000;
+
+/// Fault of a running program, with the
+/// instruction pointer of the instruction that
+/// caused it.
+#[derive(
+    Debug, Clone, Copy, Error, Diagnostic, PartialEq, Eq,
+)]
+pub enum VmError {
+    #[error("invalid opcode {opcode} at ip {ip}")]
+    #[diagnostic(
+        code(day_17::vm::invalid_opcode),
+        help("opcodes go from 0 (adv) to 7 (cdv)")
+    )]
+    InvalidOpcode { ip: usize, opcode: u8 },
+    #[error("invalid combo operand {operand} at ip {ip}")]
+    #[diagnostic(
+        code(day_17::vm::invalid_operand),
+        help(
+            "combo operands go from 0 to 6, 7 is \
+             reserved"
+        )
+    )]
+    InvalidOperand { ip: usize, operand: u8 },
+    #[error(
+        "shifting register A by {shift} bits overflows \
+         at ip {ip}"
+    )]
+    #[diagnostic(code(day_17::vm::shift_overflow))]
+    ShiftOverflow { ip: usize, shift: u64 },
+    #[error(
+        "no halt after {limit} instructions, stopped at \
+         ip {ip}"
+    )]
+    #[diagnostic(
+        code(day_17::vm::step_limit),
+        help("raise the budget with `--step-limit`")
+    )]
+    StepLimitExceeded { ip: usize, limit: usize },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+pub(crate) enum Opcode {
+    /// The adv instruction (opcode 0) performs
+    /// division. The numerator is the value in
+    /// the A register. The denominator is found
+    /// by raising 2 to the power of the
+    /// instruction's combo operand. (So, an
+    /// operand of 2 would divide A by 4 (2^2); an
+    /// operand of 5 would divide A by 2^B.) The
+    /// result of the division operation is
+    /// truncated to an integer and then written
+    /// to the A register.
+    Adv,
+    /// The bxl instruction (opcode 1) calculates
+    /// the bitwise XOR of register B and the
+    /// instruction's literal operand, then
+    /// stores the result in register B.
+    Bxl,
+
+    /// The bst instruction (opcode 2) calculates
+    /// the value of its combo operand modulo
+    /// 8 (thereby keeping only its lowest 3
+    /// bits), then writes that value to the B
+    /// register.
+    Bst,
+
+    /// The jnz instruction (opcode 3) does
+    /// nothing if the A register is 0.
+    /// However, if the A register is not
+    /// zero, it jumps by setting the instruction
+    /// pointer to the value of its literal
+    /// operand; if this instruction jumps,
+    /// the instruction pointer is not
+    /// increased by 2 after this instruction.
+    Jnz,
+
+    /// The bxc instruction (opcode 4) calculates
+    /// the bitwise XOR of register B and
+    /// register C, then stores the result in
+    /// register B. (For legacy reasons, this
+    /// instruction reads an operand but
+    /// ignores it.)
+    Bxc,
+
+    /// The out instruction (opcode 5) calculates
+    /// the value of its combo operand modulo
+    /// 8, then outputs that value. (If a
+    /// program outputs multiple values, they
+    /// are separated by commas.)
+    Out,
+
+    /// The bdv instruction (opcode 6) works
+    /// exactly like the adv instruction
+    /// except that the result is
+    /// stored in the B register. (The numerator
+    /// is still read from the A register.)
+    Bdv,
+
+    /// The cdv instruction (opcode 7) works
+    /// exactly like the adv instruction
+    /// except that the result is
+    /// stored in the C register. (The numerator
+    /// is still read from the A register.)
+    Cdv,
+}
+
+impl TryFrom<u8> for Opcode {
+    type Error = u8;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        use Opcode::*;
+        Ok(match code {
+            0 => Adv,
+            1 => Bxl,
+            2 => Bst,
+            3 => Jnz,
+            4 => Bxc,
+            5 => Out,
+            6 => Bdv,
+            7 => Cdv,
+            code => return Err(code),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "A={} B={} C={}",
+            self.a, self.b, self.c
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Computer {
+    pub(crate) register_a: u64,
+    pub(crate) register_b: u64,
+    pub(crate) register_c: u64,
+    pub(crate) instruction_pointer: usize,
+
+    pub(crate) program: Vec<u8>,
+
+    /// instructions executed so far
+    pub(crate) steps: usize,
+    /// instructions executed before failing with
+    /// [`VmError::StepLimitExceeded`]
+    pub(crate) step_limit: usize,
+    /// set once execution failed, ending the
+    /// iterator
+    pub(crate) faulted: bool,
+}
+
+/// Executes one instruction per item, with the
+/// value it output if any.
+impl Iterator for Computer {
+    type Item = Result<Option<u8>, VmError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.faulted
+            || self.instruction_pointer + 1
+                >= self.program.len()
+        {
+            return None;
+        }
+        let result = self.step();
+        self.faulted = result.is_err();
+        Some(result)
+    }
+}
+
+impl Computer {
+    pub fn new(
+        registers: [u64; 3],
+        program: Vec<u8>,
+    ) -> Self {
+        Computer {
+            register_a: registers[0],
+            register_b: registers[1],
+            register_c: registers[2],
+            instruction_pointer: 0,
+            program,
+            steps: 0,
+            step_limit: DEFAULT_STEP_LIMIT,
+            faulted: false,
+        }
+    }
+
+    pub fn registers(&self) -> Registers {
+        Registers {
+            a: self.register_a,
+            b: self.register_b,
+            c: self.register_c,
+        }
+    }
+
+    pub fn set_register_a(&mut self, value: u64) {
+        self.register_a = value;
+    }
+
+    /// Values output until the program halts or
+    /// fails.
+    pub fn outputs(
+        self,
+    ) -> impl Iterator<Item = Result<u8, VmError>> {
+        self.filter_map(Result::transpose)
+    }
+
+    fn step(&mut self) -> Result<Option<u8>, VmError> {
+        let ip = self.instruction_pointer;
+        if self.steps >= self.step_limit {
+            return Err(VmError::StepLimitExceeded {
+                ip,
+                limit: self.step_limit,
+            });
+        }
+        self.steps += 1;
+
+        let instruction = Opcode::try_from(
+            self.program[ip],
+        )
+        .map_err(|opcode| {
+            VmError::InvalidOpcode { ip, opcode }
+        })?;
+        let operand = self.program[ip + 1];
+
+        let mut out = None;
+        match &instruction {
+            Opcode::Adv => {
+                self.adv(operand)?;
+            }
+            Opcode::Bxl => {
+                self.bxl(operand);
+            }
+            Opcode::Bst => {
+                self.bst(operand)?;
+            }
+            Opcode::Jnz => {
+                self.jnz(operand);
+            }
+            Opcode::Bxc => {
+                self.bxc(operand);
+            }
+            Opcode::Out => {
+                out = Some(self.out(operand)?);
+            }
+            Opcode::Bdv => {
+                self.bdv(operand)?;
+            }
+            Opcode::Cdv => {
+                self.cdv(operand)?;
+            }
+        }
+        if instruction != Opcode::Jnz {
+            self.instruction_pointer += 2;
+        }
+
+        Ok(out)
+    }
+
+    /// There are two types of operands; each
+    /// instruction specifies the type of its
+    /// operand. The value of a literal operand is
+    /// the operand itself. For example, the value
+    /// of the literal operand 7 is the number 7.
+    /// The value of a combo operand can be found
+    /// as follows:
+    ///
+    /// Combo operands 0 through 3 represent
+    /// literal values 0 through 3.
+    /// Combo operand 4 represents the value of
+    /// register A. Combo operand 5 represents
+    /// the value of register B. Combo operand
+    /// 6 represents the value of register C.
+    /// Combo operand 7 is reserved and will not
+    /// appear in valid programs.
+    fn combo(&self, operand: u8) -> Result<u64, VmError> {
+        match operand {
+            0..=3 => Ok(operand as u64),
+            4 => Ok(self.register_a),
+            5 => Ok(self.register_b),
+            6 => Ok(self.register_c),
+            _ => Err(VmError::InvalidOperand {
+                ip: self.instruction_pointer,
+                operand,
+            }),
+        }
+    }
+
+    /// A divided by 2 to the power of the combo
+    /// operand, shared by adv, bdv and cdv.
+    fn divide(&self, operand: u8) -> Result<u64, VmError> {
+        let shift = self.combo(operand)?;
+        u32::try_from(shift)
+            .ok()
+            .and_then(|shift| {
+                self.register_a.checked_shr(shift)
+            })
+            .ok_or(VmError::ShiftOverflow {
+                ip: self.instruction_pointer,
+                shift,
+            })
+    }
+
+    /// The adv instruction (opcode 0) performs
+    /// division. The numerator is the value in
+    /// the A register. The denominator is found
+    /// by raising 2 to the power of the
+    /// instruction's combo operand. (So, an
+    /// operand of 2 would divide A by 4 (2^2); an
+    /// operand of 5 would divide A by 2^B.) The
+    /// result of the division operation is
+    /// truncated to an integer and then written
+    /// to the A register.
+    fn adv(&mut self, operand: u8) -> Result<(), VmError> {
+        self.register_a = self.divide(operand)?;
+        Ok(())
+    }
+
+    /// The bxl instruction (opcode 1) calculates
+    /// the bitwise XOR of register B and the
+    /// instruction's literal operand, then
+    /// stores the result in register B.
+    fn bxl(&mut self, operand: u8) {
+        self.register_b ^= operand as u64;
+    }
+
+    /// The bst instruction (opcode 2) calculates
+    /// the value of its combo operand modulo
+    /// 8 (thereby keeping only its lowest 3
+    /// bits), then writes that value to the B
+    /// register.
+    fn bst(&mut self, operand: u8) -> Result<(), VmError> {
+        self.register_b = self.combo(operand)? % 8;
+        Ok(())
+    }
+
+    /// The jnz instruction (opcode 3) does
+    /// nothing if the A register is 0.
+    /// However, if the A register is not
+    /// zero, it jumps by setting the instruction
+    /// pointer to the value of its literal
+    /// operand; if this instruction jumps,
+    /// the instruction pointer is not
+    /// increased by 2 after this instruction.
+    fn jnz(&mut self, operand: u8) {
+        self.instruction_pointer = if self.register_a == 0 {
+            self.instruction_pointer + 2usize
+        } else {
+            operand as usize
+        }
+    }
+
+    /// The bxc instruction (opcode 4) calculates
+    /// the bitwise XOR of register B and
+    /// register C, then stores the result in
+    /// register B. (For legacy reasons, this
+    /// instruction reads an operand but
+    /// ignores it.)
+    fn bxc(&mut self, _operand: u8) {
+        self.register_b ^= self.register_c;
+    }
+
+    /// The out instruction (opcode 5) calculates
+    /// the value of its combo operand modulo
+    /// 8, then outputs that value. (If a
+    /// program outputs multiple values, they
+    /// are separated by commas.)
+    fn out(&mut self, operand: u8) -> Result<u8, VmError> {
+        Ok((self.combo(operand)? % 8) as u8)
+    }
+
+    /// The bdv instruction (opcode 6) works
+    /// exactly like the adv instruction
+    /// except that the result is
+    /// stored in the B register. (The numerator
+    /// is still read from the A register.)
+    fn bdv(&mut self, operand: u8) -> Result<(), VmError> {
+        self.register_b = self.divide(operand)?;
+        Ok(())
+    }
+
+    /// The cdv instruction (opcode 7) works
+    /// exactly like the adv instruction
+    /// except that the result is
+    /// stored in the C register. (The numerator
+    /// is still read from the A register.)
+    fn cdv(&mut self, operand: u8) -> Result<(), VmError> {
+        self.register_c = self.divide(operand)?;
+        Ok(())
+    }
+}
+
+pub fn parse(input: &str) -> IResult<Computer> {
+    let (input, (registers, program)) = separated_pair(
+        many_m_n(
+            3,
+            3,
+            delimited(
+                tag("Register "),
+                cut(context(
+                    "register",
+                    separated_pair(
+                        anychar,
+                        tag(": "),
+                        complete::u64,
+                    ),
+                )),
+                line_ending,
+            ),
+        ),
+        line_ending,
+        delimited(
+            tag("Program: "),
+            cut(context(
+                "program",
+                separated_list1(tag(","), complete::u8),
+            )),
+            opt(line_ending),
+        ),
+    )(input)?;
+
+    Ok((
+        input,
+        Computer::new(
+            [
+                registers[0].1,
+                registers[1].1,
+                registers[2].1,
+            ],
+            program,
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn run(
+        program: &[u8],
+        step_limit: usize,
+    ) -> Result<Vec<u8>, VmError> {
+        let mut computer =
+            Computer::new([100, 0, 0], program.to_vec());
+        computer.step_limit = step_limit;
+        computer.outputs().collect()
+    }
+
+    #[rstest]
+    #[case(&[0, 1, 5, 4, 3, 0], Ok(vec![2, 1, 4, 6, 3, 1, 0]))]
+    #[case(&[5, 4, 8, 0], Err(VmError::InvalidOpcode { ip: 2, opcode: 8 }))]
+    #[case(&[5, 4, 5, 7], Err(VmError::InvalidOperand { ip: 2, operand: 7 }))]
+    #[case(&[6, 4], Err(VmError::ShiftOverflow { ip: 0, shift: 100 }))]
+    #[case(&[5, 4, 7, 4], Err(VmError::ShiftOverflow { ip: 2, shift: 100 }))]
+    #[case(&[5, 4, 3, 0], Err(VmError::StepLimitExceeded { ip: 0, limit: 100 }))]
+    fn test_faults(
+        #[case] program: &[u8],
+        #[case] expected: Result<Vec<u8>, VmError>,
+    ) {
+        assert_eq!(expected, run(program, 100));
+    }
+
+    #[test]
+    fn test_stops_after_fault() {
+        let mut computer = Computer::new(
+            [10, 0, 0],
+            vec![5, 4, 5, 7, 5, 4],
+        );
+        assert_eq!(Some(Ok(Some(2))), computer.next());
+        assert!(matches!(computer.next(), Some(Err(_))));
+        assert_eq!(None, computer.next());
+    }
+}
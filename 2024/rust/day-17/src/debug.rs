@@ -0,0 +1,498 @@
+//! Disassembler and step debugger of the 3-bit
+//! computer, driving the `Computer` one
+//! instruction at a time.
+pub use crate::computer::Registers;
+use crate::computer::{parse, Computer, VmError};
+use aoc_parse::ParseError;
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+const MNEMONICS: [&str; 8] = [
+    "adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv",
+];
+
+/// Renders a combo operand, 4 to 6 being the
+/// registers.
+fn combo(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "A".to_string(),
+        5 => "B".to_string(),
+        6 => "C".to_string(),
+        _ => format!("?{operand}"),
+    }
+}
+
+/// Renders one instruction, e.g. `adv 3`, `bxl 5`
+/// or `out B`.
+pub fn mnemonic(opcode: u8, operand: u8) -> String {
+    match opcode {
+        // literal operand
+        1 | 3 => format!(
+            "{} {operand}",
+            MNEMONICS[opcode as usize]
+        ),
+        // operand ignored
+        4 => MNEMONICS[4].to_string(),
+        0 | 2 | 5..=7 => format!(
+            "{} {}",
+            MNEMONICS[opcode as usize],
+            combo(operand)
+        ),
+        _ => format!("??? {opcode} {operand}"),
+    }
+}
+
+/// Listing of a program, one instruction per line
+/// prefixed by its address.
+pub fn disassemble(program: &[u8]) -> String {
+    let mut listing = String::new();
+    for (index, chunk) in program.chunks(2).enumerate() {
+        let text = match chunk {
+            [opcode, operand] => {
+                mnemonic(*opcode, *operand)
+            }
+            [opcode] => format!("{opcode} (no operand)"),
+            _ => unreachable!(),
+        };
+        writeln!(listing, "{:>2}: {text}", index * 2)
+            .unwrap();
+    }
+    listing
+}
+
+/// An executed instruction, with the registers
+/// after it ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub ip: usize,
+    pub instruction: String,
+    pub registers: Registers,
+    pub output: Option<u8>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:>2}: {:<6} {}",
+            self.ip, self.instruction, self.registers
+        )?;
+        if let Some(output) = self.output {
+            write!(f, " out={output}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What a breakpoint looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Ip,
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(self, left: u64, right: u64) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+}
+
+/// Condition checked before each instruction,
+/// written like `ip=6`, `A==0` or `B>=4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub target: Target,
+    pub comparison: Comparison,
+    pub value: u64,
+}
+
+impl Breakpoint {
+    pub fn hit(
+        &self,
+        ip: usize,
+        registers: Registers,
+    ) -> bool {
+        let left = match self.target {
+            Target::Ip => ip as u64,
+            Target::A => registers.a,
+            Target::B => registers.b,
+            Target::C => registers.c,
+        };
+        self.comparison.holds(left, self.value)
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let operator = |c: char| "=!<>".contains(c);
+        let start = s.find(operator).ok_or_else(|| {
+            format!(
+                "`{s}` has no comparison, expected e.g. \
+                 `ip=6` or `A==0`"
+            )
+        })?;
+        let (target, rest) = s.split_at(start);
+        let end = rest
+            .find(|c: char| !operator(c))
+            .unwrap_or(rest.len());
+        let (symbol, value) = rest.split_at(end);
+
+        let target = match target.trim() {
+            "ip" | "IP" => Target::Ip,
+            "A" | "a" => Target::A,
+            "B" | "b" => Target::B,
+            "C" | "c" => Target::C,
+            other => {
+                return Err(format!(
+                    "unknown register `{other}`, expected \
+                     ip, A, B or C"
+                ))
+            }
+        };
+        let comparison = match symbol {
+            "=" | "==" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            other => {
+                return Err(format!(
+                    "unknown comparison `{other}`"
+                ))
+            }
+        };
+        let value =
+            value.trim().parse().map_err(|err| {
+                format!("invalid value `{value}`: {err}")
+            })?;
+
+        Ok(Breakpoint {
+            target,
+            comparison,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let target = match self.target {
+            Target::Ip => "ip",
+            Target::A => "A",
+            Target::B => "B",
+            Target::C => "C",
+        };
+        write!(
+            f,
+            "{target}{}{}",
+            self.comparison.symbol(),
+            self.value
+        )
+    }
+}
+
+/// Why [`Debugger::run`] returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stop {
+    /// the instruction pointer left the program
+    Halted,
+    /// the breakpoint held before the next
+    /// instruction
+    Breakpoint(Breakpoint),
+    /// the step limit was reached
+    Limit,
+    /// the next instruction failed
+    Fault(VmError),
+}
+
+/// Runs a program step by step, recording every
+/// executed instruction.
+pub struct Debugger {
+    computer: Computer,
+    breakpoints: Vec<Breakpoint>,
+    steps: Vec<Step>,
+}
+
+impl Debugger {
+    /// Loads the computer described by the puzzle
+    /// input.
+    pub fn new(input: &str) -> miette::Result<Self> {
+        let (_, mut computer) = parse(input)
+            .map_err(|err| ParseError::new(input, err))?;
+        // `run` has its own limit
+        computer.step_limit = usize::MAX;
+        Ok(Debugger {
+            computer,
+            breakpoints: vec![],
+            steps: vec![],
+        })
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.computer.program
+    }
+
+    pub fn ip(&self) -> usize {
+        self.computer.instruction_pointer
+    }
+
+    pub fn registers(&self) -> Registers {
+        self.computer.registers()
+    }
+
+    pub fn set_register_a(&mut self, value: u64) {
+        self.computer.register_a = value;
+    }
+
+    pub fn add_breakpoint(
+        &mut self,
+        breakpoint: Breakpoint,
+    ) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    /// Every instruction executed so far.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Values output so far.
+    pub fn output(&self) -> Vec<u8> {
+        self.steps
+            .iter()
+            .filter_map(|step| step.output)
+            .collect()
+    }
+
+    /// Executes a single instruction, `None` once
+    /// the program halted.
+    pub fn step(
+        &mut self,
+    ) -> Result<Option<&Step>, VmError> {
+        let ip = self.ip();
+        let instruction =
+            match self.program().get(ip..ip + 2) {
+                Some([opcode, operand]) => {
+                    mnemonic(*opcode, *operand)
+                }
+                _ => return Ok(None),
+            };
+        let Some(output) =
+            self.computer.next().transpose()?
+        else {
+            return Ok(None);
+        };
+        self.steps.push(Step {
+            ip,
+            instruction,
+            registers: self.registers(),
+            output,
+        });
+        Ok(self.steps.last())
+    }
+
+    /// Executes up to `limit` instructions,
+    /// stopping early when the program halts or a
+    /// breakpoint holds. The breakpoints are not
+    /// checked before the first instruction so a
+    /// stopped run can be resumed.
+    pub fn run(&mut self, limit: usize) -> Stop {
+        for count in 0..limit {
+            if self.halted() {
+                return Stop::Halted;
+            }
+            let (ip, registers) =
+                (self.ip(), self.registers());
+            let hit = self.breakpoints.iter().find(
+                |breakpoint| breakpoint.hit(ip, registers),
+            );
+            if let (true, Some(breakpoint)) =
+                (count > 0, hit)
+            {
+                return Stop::Breakpoint(*breakpoint);
+            }
+            if let Err(err) = self.step() {
+                return Stop::Fault(err);
+            }
+        }
+        if self.halted() {
+            Stop::Halted
+        } else {
+            Stop::Limit
+        }
+    }
+
+    /// Whether the instruction pointer left the
+    /// program.
+    pub fn halted(&self) -> bool {
+        self.ip() + 1 >= self.program().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            " 0: adv 1\n 2: out A\n 4: jnz 0\n",
+            disassemble(&[0, 1, 5, 4, 3, 0])
+        );
+        assert_eq!(
+            " 0: bst A\n 2: bxl 5\n 4: cdv B\n 6: bxc\n \
+             8: out B\n10: ??? 9 7\n12: 2 (no operand)\n",
+            disassemble(&[
+                2, 4, 1, 5, 7, 5, 4, 3, 5, 5, 9, 7, 2
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_breakpoint() {
+        assert_eq!(
+            Ok(Breakpoint {
+                target: Target::Ip,
+                comparison: Comparison::Eq,
+                value: 6
+            }),
+            "ip=6".parse()
+        );
+        assert_eq!(
+            Ok(Breakpoint {
+                target: Target::B,
+                comparison: Comparison::Ge,
+                value: 4
+            }),
+            "B >= 4".parse()
+        );
+        assert_eq!(
+            "A==0",
+            "A == 0"
+                .parse::<Breakpoint>()
+                .unwrap()
+                .to_string()
+        );
+        assert!("D=1".parse::<Breakpoint>().is_err());
+        assert!("A~1".parse::<Breakpoint>().is_err());
+        assert!("A=x".parse::<Breakpoint>().is_err());
+    }
+
+    #[test]
+    fn test_trace() -> miette::Result<()> {
+        let mut debugger = Debugger::new(EXAMPLE)?;
+        debugger.add_breakpoint("A<10".parse().unwrap());
+
+        let stop = debugger.run(1_000);
+        assert_eq!(
+            Stop::Breakpoint("A<10".parse().unwrap()),
+            stop
+        );
+        assert_eq!(
+            Registers { a: 5, b: 0, c: 0 },
+            debugger.registers()
+        );
+        assert_eq!(
+            vec![4, 6, 3, 5, 6, 3],
+            debugger.output()
+        );
+        assert_eq!(
+            " 2: out A  A=182 B=0 C=0 out=6",
+            debugger.steps()[4].to_string()
+        );
+
+        // resumes past the breakpoint that stopped
+        // it, holding again one step later
+        let steps = debugger.steps().len();
+        assert_eq!(stop, debugger.run(1_000));
+        assert_eq!(steps + 1, debugger.steps().len());
+        assert_eq!(
+            vec![4, 6, 3, 5, 6, 3, 5],
+            debugger.output()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_halt() -> miette::Result<()> {
+        let mut debugger = Debugger::new(EXAMPLE)?;
+        debugger.add_breakpoint("ip>4".parse().unwrap());
+        assert_eq!(Stop::Halted, debugger.run(1_000));
+        assert_eq!(
+            vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0],
+            debugger.output()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit() -> miette::Result<()> {
+        let mut debugger = Debugger::new(EXAMPLE)?;
+        assert_eq!(Stop::Limit, debugger.run(4));
+        assert_eq!(4, debugger.steps().len());
+        assert_eq!(2, debugger.ip());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fault() -> miette::Result<()> {
+        let mut debugger = Debugger::new(
+            "Register A: 7
+Register B: 0
+Register C: 0
+
+Program: 5,4,5,7",
+        )?;
+        assert_eq!(
+            Stop::Fault(VmError::InvalidOperand {
+                ip: 2,
+                operand: 7
+            }),
+            debugger.run(10)
+        );
+        assert_eq!(vec![7], debugger.output());
+        Ok(())
+    }
+}
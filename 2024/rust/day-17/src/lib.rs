@@ -1,2 +1,26 @@
+pub mod compiled;
+pub mod computer;
+pub mod debug;
 pub mod part1;
 pub mod part2;
+pub mod quine;
+
+use computer::DEFAULT_STEP_LIMIT;
+
+/// Puzzle parameters, the defaults are the real
+/// puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// instructions a program may execute before
+    /// it is considered stuck in a loop
+    #[clap(long, default_value_t = Params::default().step_limit)]
+    pub step_limit: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            step_limit: DEFAULT_STEP_LIMIT,
+        }
+    }
+}
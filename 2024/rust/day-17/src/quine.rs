@@ -0,0 +1,370 @@
+//! Search of the register A value making a
+//! program output a given sequence, usually the
+//! program itself.
+//!
+//! The program is analysed first: the search only
+//! works for a single loop that shifts A right by
+//! a fixed number of bits per iteration and keeps
+//! no state in B or C between iterations, so the
+//! output of an iteration only depends on the
+//! bits of A not shifted out yet. The value is
+//! then built from its highest bits down, one
+//! iteration at a time, starting from the last
+//! output, depth first so only one candidate per
+//! iteration is kept at a time.
+use crate::{
+    compiled::Compiled,
+    computer::{Computer, Registers},
+};
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Why a program can not be searched.
+#[derive(Debug, Error, Diagnostic, PartialEq, Eq)]
+#[diagnostic(
+    code(day_17::unsupported),
+    help(
+        "the solver needs a single loop ending with \
+         `jnz 0` that shifts A by constant `adv` \
+         operands and sets B and C before reading them"
+    )
+)]
+pub enum Unsupported {
+    #[error(
+        "a program of {length} values ends in the \
+         middle of an instruction"
+    )]
+    OddLength { length: usize },
+    #[error(
+        "the program does not end with `jnz 0`, so it \
+         is not a loop over the whole program"
+    )]
+    NoLoop,
+    #[error(
+        "the jump at ip {ip} is not the final `jnz 0`"
+    )]
+    Jump { ip: usize },
+    #[error(
+        "opcode {opcode} at ip {ip} is not an instruction"
+    )]
+    InvalidOpcode { ip: usize, opcode: u8 },
+    #[error(
+        "combo operand {operand} at ip {ip} is reserved"
+    )]
+    ReservedOperand { ip: usize, operand: u8 },
+    #[error(
+        "the `adv` at ip {ip} shifts A by a register, \
+         so the bits consumed per output are not fixed"
+    )]
+    RegisterShift { ip: usize },
+    #[error(
+        "register {register} is read at ip {ip} before \
+         being set, so it carries state between \
+         iterations"
+    )]
+    CarriedRegister { register: char, ip: usize },
+    #[error(
+        "A is never shifted right, so the loop never ends"
+    )]
+    NoShift,
+    #[error(
+        "A is shifted by {shift} bits per iteration, \
+         trying every value of that many bits is too \
+         slow past {MAX_SHIFT}"
+    )]
+    ShiftTooWide { shift: u32 },
+    #[error("the loop does not output anything")]
+    NoOutput,
+    #[error(
+        "{length} values can not be output by a loop \
+         outputting {outputs} values per iteration"
+    )]
+    Length { length: usize, outputs: usize },
+}
+
+/// Widest shift per iteration searched, the
+/// search tries `1 << shift` values per output.
+pub const MAX_SHIFT: u32 = 16;
+
+/// Structure of a supported program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analysis {
+    /// bits of A consumed per iteration
+    pub shift: u32,
+    /// values output per iteration
+    pub outputs: usize,
+}
+
+/// Checks the program is a loop the search can
+/// handle.
+pub fn analyse(
+    program: &[u8],
+) -> Result<Analysis, Unsupported> {
+    if program.len() % 2 != 0 {
+        return Err(Unsupported::OddLength {
+            length: program.len(),
+        });
+    }
+    if !program.ends_with(&[3, 0]) {
+        return Err(Unsupported::NoLoop);
+    }
+    let body = &program[..program.len() - 2];
+
+    let mut shift = 0;
+    let mut outputs = 0;
+    // whether B and C were set in this iteration
+    let mut set = [false; 2];
+
+    for (index, instruction) in body.chunks(2).enumerate() {
+        let ip = index * 2;
+        let [opcode, operand] = *instruction else {
+            unreachable!("program length is even")
+        };
+        let combo = matches!(opcode, 0 | 2 | 5..=7);
+        if combo && operand > 6 {
+            return Err(Unsupported::ReservedOperand {
+                ip,
+                operand,
+            });
+        }
+        // register read by the combo operand, 0
+        // being A
+        let combo_register = (combo && operand >= 4)
+            .then(|| operand as usize - 4);
+        if let Some(register) = combo_register {
+            check_set(register, ip, &set)?;
+        }
+
+        match opcode {
+            0 if combo_register.is_some() => {
+                return Err(Unsupported::RegisterShift {
+                    ip,
+                });
+            }
+            0 => shift += operand as u32,
+            1 => check_set(1, ip, &set)?,
+            2 | 6 => set[0] = true,
+            3 => return Err(Unsupported::Jump { ip }),
+            4 => {
+                check_set(1, ip, &set)?;
+                check_set(2, ip, &set)?;
+            }
+            5 => outputs += 1,
+            7 => set[1] = true,
+            opcode => {
+                return Err(Unsupported::InvalidOpcode {
+                    ip,
+                    opcode,
+                })
+            }
+        }
+    }
+
+    if shift == 0 {
+        return Err(Unsupported::NoShift);
+    }
+    if shift > MAX_SHIFT {
+        return Err(Unsupported::ShiftTooWide { shift });
+    }
+    if outputs == 0 {
+        return Err(Unsupported::NoOutput);
+    }
+    Ok(Analysis { shift, outputs })
+}
+
+/// Fails when register B (1) or C (2) is read
+/// before being set in the iteration.
+fn check_set(
+    register: usize,
+    ip: usize,
+    set: &[bool; 2],
+) -> Result<(), Unsupported> {
+    match register {
+        1 | 2 if !set[register - 1] => {
+            Err(Unsupported::CarriedRegister {
+                register: ['B', 'C'][register - 1],
+                ip,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Lowest value of register A making `computer`
+/// output exactly `target`, `None` when there is
+/// none.
+pub(crate) fn solve(
+    computer: &Computer,
+    target: &[u8],
+) -> Result<Option<u64>, Unsupported> {
+    let Analysis { shift, outputs } =
+        analyse(&computer.program)?;
+    if target.is_empty() || target.len() % outputs != 0 {
+        return Err(Unsupported::Length {
+            length: target.len(),
+            outputs,
+        });
+    }
+
+    let search = Search {
+        compiled: Compiled::new(computer),
+        registers: computer.registers(),
+        target,
+        shift,
+        outputs,
+    };
+    Ok(search.from(0, outputs))
+}
+
+/// Candidates for register A, built `shift` bits
+/// at a time.
+struct Search<'a> {
+    compiled: Compiled,
+    registers: Registers,
+    target: &'a [u8],
+    shift: u32,
+    outputs: usize,
+}
+
+impl Search<'_> {
+    /// Lowest value starting with the bits of
+    /// `prefix` that outputs the last `len`
+    /// values of the target.
+    ///
+    /// The last iteration leaves A at zero, so it
+    /// starts with at most `shift` bits. Each
+    /// earlier iteration adds `shift` lower bits,
+    /// tried in increasing order so the first
+    /// match is the lowest.
+    fn from(&self, prefix: u64, len: usize) -> Option<u64> {
+        if prefix.leading_zeros() < self.shift {
+            // A would not fit in the register
+            return None;
+        }
+        let next = prefix << self.shift;
+        (next..next + (1 << self.shift)).find_map(|a| {
+            let to_search = self.compiled.run(Registers {
+                a,
+                ..self.registers
+            });
+
+            // Check tail of the target sequence, a
+            // candidate making the program fail does
+            // not match
+            let tail_iterator = self
+                .target
+                .iter()
+                .skip(self.target.len() - len)
+                .copied()
+                .map(Ok);
+            if !check_iterator(to_search, tail_iterator) {
+                return None;
+            }
+            if len >= self.target.len() {
+                return Some(a);
+            }
+            self.from(a, len + self.outputs)
+        })
+    }
+}
+
+fn check_iterator<I>(
+    mut to_search: impl Iterator<Item = I>,
+    compare: impl Iterator<Item = I>,
+) -> bool
+where
+    I: PartialEq,
+{
+    for expected in compare {
+        let Some(actual) = to_search.next() else {
+            // Not enough values in output
+            return false;
+        };
+        if expected != actual {
+            // Not the expected value
+            return false;
+        }
+    }
+
+    // Check iterator was all consumed
+    to_search.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn computer(program: &[u8]) -> Computer {
+        Computer::new([0; 3], program.to_vec())
+    }
+
+    fn run(computer: &Computer, a: u64) -> Vec<u8> {
+        let mut computer = computer.clone();
+        computer.register_a = a;
+        computer
+            .outputs()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(&[0, 3, 5, 4, 3, 0], Ok(Analysis { shift: 3, outputs: 1 }))]
+    #[case(&[0, 1, 2, 4, 5, 5, 0, 2, 3, 0], Ok(Analysis { shift: 3, outputs: 1 }))]
+    #[case(&[2, 4, 5, 5, 0, 2, 5, 4, 3, 0], Ok(Analysis { shift: 2, outputs: 2 }))]
+    #[case(&[0, 3, 5, 4, 3], Err(Unsupported::OddLength { length: 5 }))]
+    #[case(&[0, 3, 5, 4], Err(Unsupported::NoLoop))]
+    #[case(&[0, 3, 3, 4, 5, 4, 3, 0], Err(Unsupported::Jump { ip: 2 }))]
+    #[case(&[0, 3, 9, 4, 3, 0], Err(Unsupported::InvalidOpcode { ip: 2, opcode: 9 }))]
+    #[case(&[0, 3, 5, 7, 3, 0], Err(Unsupported::ReservedOperand { ip: 2, operand: 7 }))]
+    #[case(&[2, 4, 0, 5, 5, 4, 3, 0], Err(Unsupported::RegisterShift { ip: 2 }))]
+    #[case(&[1, 3, 0, 3, 5, 5, 3, 0], Err(Unsupported::CarriedRegister { register: 'B', ip: 0 }))]
+    #[case(&[2, 4, 4, 0, 0, 3, 5, 5, 3, 0], Err(Unsupported::CarriedRegister { register: 'C', ip: 2 }))]
+    #[case(&[2, 4, 5, 5, 3, 0], Err(Unsupported::NoShift))]
+    #[case(&[0, 3, 2, 4, 3, 0], Err(Unsupported::NoOutput))]
+    #[case(&[[0, 3]; 22].concat().into_iter().chain([5, 4, 3, 0]).collect::<Vec<_>>(), Err(Unsupported::ShiftTooWide { shift: 66 }))]
+    fn test_analyse(
+        #[case] program: &[u8],
+        #[case] expected: Result<Analysis, Unsupported>,
+    ) {
+        assert_eq!(expected, analyse(program));
+    }
+
+    #[rstest]
+    // bst A; bxl 3; out B; adv 1; jnz 0
+    #[case(&[2, 4, 1, 3, 5, 5, 0, 1, 3, 0])]
+    // bst A; bxl 5; cdv B; bxc; out B; adv 2; jnz 0
+    #[case(&[2, 4, 1, 5, 7, 5, 4, 0, 5, 5, 0, 2, 3, 0])]
+    // adv 1; bst A; out B; adv 2; jnz 0
+    #[case(&[0, 1, 2, 4, 5, 5, 0, 2, 3, 0])]
+    // bst A; out B; adv 2; out A; adv 2; jnz 0
+    #[case(&[2, 4, 5, 5, 0, 2, 5, 4, 0, 2, 3, 0])]
+    fn test_solve(#[case] program: &[u8]) {
+        let computer = computer(program);
+        for a in [0, 1, 6, 2925, 0b1101_0110_1001_1100] {
+            let target = run(&computer, a);
+            let found = solve(&computer, &target)
+                .unwrap()
+                .expect("a is a solution");
+            assert!(found <= a, "{found} > {a}");
+            assert_eq!(target, run(&computer, found));
+        }
+    }
+
+    #[test]
+    fn test_no_solution() {
+        // out 0 can only output zeros
+        let computer = computer(&[5, 0, 0, 3, 3, 0]);
+        assert_eq!(Ok(None), solve(&computer, &[1, 0]));
+        assert_eq!(
+            Err(Unsupported::Length {
+                length: 3,
+                outputs: 2
+            }),
+            solve(
+                &self::computer(&[5, 0, 5, 0, 0, 3, 3, 0]),
+                &[0, 0, 0]
+            )
+        );
+    }
+}
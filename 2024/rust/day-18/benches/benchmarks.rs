@@ -6,17 +6,81 @@ fn main() {
 }
 
 #[divan::bench]
-fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+fn part1(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        1,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part1::process(divan::black_box(&input)).unwrap()
+    });
 }
 
 #[divan::bench]
-fn part2() {
-    part2::process(divan::black_box(include_str!(
-        "../input2.txt",
-    )))
-    .unwrap();
+fn part2(bencher: divan::Bencher) {
+    let Some(input) = aoc_input::bench_input(
+        env!("CARGO_MANIFEST_DIR"),
+        2,
+    ) else {
+        return;
+    };
+    bencher.bench(|| {
+        part2::process(divan::black_box(&input)).unwrap()
+    });
+}
+
+/// Largest coordinate of the synthetic memory
+/// space, 500 tiles wide.
+const SYNTHETIC_SIZE: i32 = 499;
+
+/// 100k distinct bytes falling at random over the
+/// synthetic space, enough to cut the exit off.
+fn synthetic_bytes() -> Vec<glam::IVec2> {
+    let len = (SYNTHETIC_SIZE + 1) as u64;
+    let mut tiles = (0..len * len).collect::<Vec<_>>();
+    let mut seed = 12345u64;
+    // Fisher-Yates with a linear congruential
+    // generator, the same every run
+    for i in (1..tiles.len()).rev() {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        tiles.swap(i, (seed >> 33) as usize % (i + 1));
+    }
+    tiles
+        .into_iter()
+        .filter(|tile| *tile != 0 && *tile != len * len - 1)
+        .take(100_000)
+        .map(|tile| {
+            glam::IVec2::new(
+                (tile % len) as i32,
+                (tile / len) as i32,
+            )
+        })
+        .collect()
+}
+
+#[divan::bench(
+    args = [
+        blocking::Solver::UnionFind,
+        blocking::Solver::BinarySearch,
+        blocking::Solver::Astar,
+    ],
+    sample_count = 5,
+)]
+fn first_blocking(
+    bencher: divan::Bencher,
+    solver: blocking::Solver,
+) {
+    let bytes = synthetic_bytes();
+    bencher.bench(|| {
+        solver
+            .first_blocking(
+                divan::black_box(&bytes),
+                SYNTHETIC_SIZE,
+            )
+            .expect("the synthetic bytes block the exit")
+    });
 }
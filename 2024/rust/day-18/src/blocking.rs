@@ -0,0 +1,358 @@
+//! First falling byte that cuts the exit off from
+//! the start.
+//!
+//! Three ways to find it:
+//!
+//! - undoing the bytes from the last one with a
+//!   union-find of the safe tiles, until the
+//!   start and the exit join,
+//! - a binary search over the number of fallen
+//!   bytes, with a BFS per guess,
+//! - the A* search again each time a byte falls
+//!   on the current path, the original approach,
+//!   kept to compare with.
+use glam::IVec2;
+use pathfinding::prelude::astar;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(
+    clap::ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+)]
+pub enum Solver {
+    /// remove bytes from the last one, joining
+    /// the tiles they free
+    #[default]
+    UnionFind,
+    /// binary search on the number of bytes, with
+    /// a BFS each
+    BinarySearch,
+    /// search a path again each time a byte falls
+    /// on it
+    Astar,
+}
+
+impl Solver {
+    /// Index in `bytes` of the first byte after
+    /// which the exit at `size,size` cannot be
+    /// reached, `None` if it always can.
+    pub fn first_blocking(
+        self,
+        bytes: &[IVec2],
+        size: i32,
+    ) -> Option<usize> {
+        match self {
+            Solver::UnionFind => union_find(bytes, size),
+            Solver::BinarySearch => {
+                binary_search(bytes, size)
+            }
+            Solver::Astar => incremental_astar(bytes, size),
+        }
+    }
+}
+
+/// The memory space, `size + 1` tiles wide and
+/// high.
+struct Space {
+    size: i32,
+}
+
+impl Space {
+    fn index(&self, position: IVec2) -> Option<usize> {
+        let range = 0..=self.size;
+        (range.contains(&position.x)
+            && range.contains(&position.y))
+        .then(|| {
+            (position.y * (self.size + 1) + position.x)
+                as usize
+        })
+    }
+
+    fn len(&self) -> usize {
+        ((self.size + 1) * (self.size + 1)) as usize
+    }
+
+    fn neighbors(
+        &self,
+        position: IVec2,
+    ) -> impl Iterator<Item = (IVec2, usize)> + '_ {
+        [IVec2::NEG_Y, IVec2::NEG_X, IVec2::Y, IVec2::X]
+            .into_iter()
+            .map(move |offset| position + offset)
+            .filter_map(|next| {
+                self.index(next).map(|index| (next, index))
+            })
+    }
+
+    /// Step each tile is corrupted at,
+    /// `usize::MAX` for the ones that stay
+    /// safe. Bytes outside the space are
+    /// ignored.
+    fn fallen_at(&self, bytes: &[IVec2]) -> Vec<usize> {
+        let mut fallen_at = vec![usize::MAX; self.len()];
+        for (step, byte) in bytes.iter().enumerate() {
+            if let Some(index) = self.index(*byte) {
+                fallen_at[index] =
+                    fallen_at[index].min(step);
+            }
+        }
+        fallen_at
+    }
+}
+
+/// Disjoint sets of tiles, with path halving and
+/// union by size.
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            self.parents[index] =
+                self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+fn union_find(bytes: &[IVec2], size: i32) -> Option<usize> {
+    let space = Space { size };
+    let fallen_at = space.fallen_at(bytes);
+    let (start, exit) = (
+        space.index(IVec2::ZERO)?,
+        space.index(IVec2::splat(size))?,
+    );
+    let mut sets = UnionFind::new(space.len());
+    let mut safe = fallen_at
+        .iter()
+        .map(|step| *step == usize::MAX)
+        .collect::<Vec<_>>();
+
+    let free = |sets: &mut UnionFind,
+                safe: &[bool],
+                position: IVec2| {
+        let index = space.index(position).unwrap();
+        for (_, next) in space.neighbors(position) {
+            if safe[next] {
+                sets.union(index, next);
+            }
+        }
+    };
+    for y in 0..=size {
+        for x in 0..=size {
+            let position = IVec2::new(x, y);
+            if safe[space.index(position).unwrap()] {
+                free(&mut sets, &safe, position);
+            }
+        }
+    }
+    let joined = |sets: &mut UnionFind, safe: &[bool]| {
+        safe[start]
+            && safe[exit]
+            && sets.find(start) == sets.find(exit)
+    };
+    if joined(&mut sets, &safe) {
+        return None;
+    }
+
+    for (step, byte) in bytes.iter().enumerate().rev() {
+        let Some(index) = space.index(*byte) else {
+            continue;
+        };
+        // a tile hit twice is safe before the first
+        if fallen_at[index] != step {
+            continue;
+        }
+        safe[index] = true;
+        free(&mut sets, &safe, *byte);
+        if joined(&mut sets, &safe) {
+            return Some(step);
+        }
+    }
+    None
+}
+
+/// Whether the exit can be reached once the bytes
+/// before `step` fell.
+fn reachable(
+    space: &Space,
+    fallen_at: &[usize],
+    step: usize,
+) -> bool {
+    let safe = |index: usize| fallen_at[index] >= step;
+    let exit = IVec2::splat(space.size);
+    let start = space.index(IVec2::ZERO).unwrap();
+    if !safe(start) {
+        return false;
+    }
+    let mut seen = vec![false; space.len()];
+    seen[start] = true;
+    let mut queue = VecDeque::from([IVec2::ZERO]);
+    while let Some(position) = queue.pop_front() {
+        if position == exit {
+            return true;
+        }
+        for (next, index) in space.neighbors(position) {
+            if safe(index) && !seen[index] {
+                seen[index] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+fn binary_search(
+    bytes: &[IVec2],
+    size: i32,
+) -> Option<usize> {
+    let space = Space { size };
+    space.index(IVec2::splat(size))?;
+    let fallen_at = space.fallen_at(bytes);
+    // number of bytes the exit is still reachable
+    // after, the last of them blocks it
+    let steps = (0..=bytes.len()).collect::<Vec<_>>();
+    let blocked = steps.partition_point(|step| {
+        reachable(&space, &fallen_at, *step)
+    });
+    (blocked <= bytes.len()).then(|| blocked - 1)
+}
+
+fn incremental_astar(
+    bytes: &[IVec2],
+    size: i32,
+) -> Option<usize> {
+    let grid_size = IVec2::splat(size);
+    let corrupted_memory = &mut HashSet::new();
+
+    let find_path = |corrupted_memory: &HashSet<IVec2>| {
+        astar(
+            &IVec2::ZERO,
+            |pos| {
+                [
+                    pos + IVec2::NEG_Y,
+                    pos + IVec2::NEG_X,
+                    pos + IVec2::Y,
+                    pos + IVec2::X,
+                ]
+                .into_iter()
+                .filter_map(|pos| {
+                    if ((0..=grid_size.x).contains(&pos.x)
+                        && (0..=grid_size.y)
+                            .contains(&pos.y))
+                        && !corrupted_memory.contains(&pos)
+                    {
+                        Some((pos, 1))
+                    } else {
+                        None
+                    }
+                })
+            },
+            |pos| (grid_size - pos).abs().element_sum(),
+            |pos| *pos == grid_size,
+        )
+        // the search starts on the start tile even
+        // when a byte fell on it
+        .filter(|_| {
+            !corrupted_memory.contains(&IVec2::ZERO)
+        })
+    };
+
+    let mut path = find_path(corrupted_memory)?;
+    for (step, pos) in bytes.iter().enumerate() {
+        corrupted_memory.insert(*pos);
+        if path.0.contains(pos) {
+            let Some(new_path) =
+                find_path(corrupted_memory)
+            else {
+                return Some(step);
+            };
+            path = new_path;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Bytes written `x,y` separated by spaces.
+    fn bytes(text: &str) -> Vec<IVec2> {
+        text.split_whitespace()
+            .map(|byte| {
+                let (x, y) = byte.split_once(',').unwrap();
+                IVec2::new(
+                    x.parse().unwrap(),
+                    y.parse().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// The puzzle's example, the 21st byte `6,1`
+    /// blocks the exit.
+    const EXAMPLE: &str =
+        "5,4 4,2 4,5 3,0 2,1 6,3 2,4 1,5 0,6 \
+        3,3 2,6 5,1 1,2 5,5 2,5 6,5 1,4 0,4 6,4 1,1 6,1 \
+        1,0 0,5 1,6 2,0";
+
+    #[rstest]
+    fn test_solvers(
+        #[values(
+            Solver::UnionFind,
+            Solver::BinarySearch,
+            Solver::Astar
+        )]
+        solver: Solver,
+        #[values(
+            (6, EXAMPLE, Some(20)),
+            // a wall across the middle row
+            (2, "0,1 1,1 2,1 0,0", Some(2)),
+            // bytes outside the space, and twice on
+            // a tile, change nothing
+            (2, "5,5 0,1 0,1 -1,1 1,1 2,1", Some(5)),
+            (2, "1,0 0,0", Some(1)),
+            (2, "1,0 2,2", Some(1)),
+            (2, "1,1 2,0", None),
+            (0, "1,0 0,0", Some(1)),
+            (3, "", None),
+        )]
+        case: (i32, &str, Option<usize>),
+    ) {
+        let (size, text, expected) = case;
+        assert_eq!(
+            expected,
+            solver.first_blocking(&bytes(text), size),
+            "{text}"
+        );
+    }
+}
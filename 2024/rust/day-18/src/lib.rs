@@ -1,2 +1,42 @@
+use blocking::Solver;
+
+pub mod blocking;
 pub mod part1;
 pub mod part2;
+
+/// Puzzle parameters, the defaults are the real
+/// puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// largest coordinate of the memory space,
+    /// the exit is at `size,size`
+    #[clap(long, default_value_t = Params::default().size)]
+    pub size: i32,
+    /// number of bytes fallen before looking for
+    /// a path, part 1 only: part 2 searches from
+    /// the first byte
+    #[clap(long, default_value_t = Params::default().bytes)]
+    pub bytes: usize,
+    /// how part 2 finds the first blocking byte
+    #[clap(long, value_enum, default_value_t = Params::default().solver)]
+    pub solver: Solver,
+}
+
+impl Params {
+    /// Parameters of the puzzle's example.
+    pub const EXAMPLE: Params = Params {
+        size: 6,
+        bytes: 12,
+        solver: Solver::UnionFind,
+    };
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            size: 70,
+            bytes: 1024,
+            solver: Solver::UnionFind,
+        }
+    }
+}
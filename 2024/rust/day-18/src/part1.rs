@@ -1,3 +1,5 @@
+use crate::Params;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
 use miette::miette;
 use nom::{
@@ -6,26 +8,25 @@ use nom::{
     combinator::{all_consuming, map, opt},
     multi::separated_list0,
     sequence::{separated_pair, terminated},
-    IResult,
 };
 use pathfinding::prelude::astar;
 use std::collections::HashSet;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, falling_bytes) =
-        parse(input).map_err(|err| {
-            miette::miette!("Parse error: {}", err)
-        })?;
+    process_with(input, &Params::default())
+}
 
-    let grid_size = if cfg!(test) {
-        IVec2::splat(6)
-    } else {
-        IVec2::splat(70)
-    };
+#[tracing::instrument(skip(input))]
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<String> {
+    let (_, falling_bytes) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
-    let simulation_length =
-        if cfg!(test) { 12 } else { 1024 };
+    let grid_size = IVec2::splat(params.size);
+    let simulation_length = params.bytes;
 
     let corrupted_memory: HashSet<IVec2> = falling_bytes
         .iter()
@@ -62,7 +63,7 @@ pub fn process(input: &str) -> miette::Result<String> {
     Ok(result.1.to_string())
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<IVec2>> {
+fn parse(input: &str) -> IResult<Vec<IVec2>> {
     all_consuming(terminated(
         separated_list0(
             line_ending,
@@ -117,7 +118,10 @@ mod tests {
         #[case] input: &str,
         #[case] expected: &str,
     ) -> miette::Result<()> {
-        assert_eq!(expected, process(input)?);
+        assert_eq!(
+            expected,
+            process_with(input, &Params::EXAMPLE)?
+        );
         Ok(())
     }
 }
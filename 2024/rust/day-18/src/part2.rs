@@ -1,3 +1,5 @@
+use crate::Params;
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
 use miette::miette;
 use nom::{
@@ -6,87 +8,31 @@ use nom::{
     combinator::{all_consuming, map, opt},
     multi::separated_list0,
     sequence::{separated_pair, terminated},
-    IResult,
 };
-use pathfinding::prelude::astar;
-use std::collections::HashSet;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, falling_bytes) =
-        parse(input).map_err(|err| {
-            miette::miette!("Parse error: {}", err)
-        })?;
-
-    let grid_size = if cfg!(test) {
-        IVec2::splat(6)
-    } else {
-        IVec2::splat(70)
-    };
-
-    let simulation_length =
-        if cfg!(test) { 12 } else { 1024 };
-
-    let corrupted_memory = &mut falling_bytes
-        .iter()
-        .take(simulation_length)
-        .cloned()
-        .collect::<HashSet<_>>();
+    process_with(input, &Params::default())
+}
 
-    let find_path = |corrupted_memory: &HashSet<IVec2>| {
-        astar(
-            &IVec2::ZERO,
-            |pos| {
-                [
-                    pos + IVec2::NEG_Y,
-                    pos + IVec2::NEG_X,
-                    pos + IVec2::Y,
-                    pos + IVec2::X,
-                ]
-                .into_iter()
-                .filter_map(|pos| {
-                    if ((0..=grid_size.x).contains(&pos.x)
-                        && (0..=grid_size.y)
-                            .contains(&pos.y))
-                        && !corrupted_memory.contains(&pos)
-                    {
-                        Some((pos, 1))
-                    } else {
-                        None
-                    }
-                })
-            },
-            |pos| (grid_size - pos).abs().element_sum(),
-            |pos| *pos == grid_size,
-        )
-    };
+#[tracing::instrument(skip(input))]
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<String> {
+    let (_, falling_bytes) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
-    let mut path = find_path(corrupted_memory)
+    let step = params
+        .solver
+        .first_blocking(&falling_bytes, params.size)
         .ok_or_else(|| miette!("No solution found"))?;
-    let falling_bytes =
-        &mut falling_bytes.iter().skip(simulation_length);
-    let last_pos = loop {
-        let pos = falling_bytes
-            .next()
-            .ok_or(miette!("No solution found"))?;
-        corrupted_memory.insert(*pos);
-        if path.0.contains(pos) {
-            let Some(new_path) =
-                find_path(corrupted_memory)
-            else {
-                break *pos;
-            };
-            path = new_path;
-        }
-    };
+    let last_pos = falling_bytes[step];
 
-    Ok(
-        format!("{},{}", last_pos.x, last_pos.y)
-            .to_string(),
-    )
+    Ok(format!("{},{}", last_pos.x, last_pos.y))
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<IVec2>> {
+fn parse(input: &str) -> IResult<Vec<IVec2>> {
     all_consuming(terminated(
         separated_list0(
             line_ending,
@@ -141,7 +87,10 @@ mod tests {
         #[case] input: &str,
         #[case] expected: &str,
     ) -> miette::Result<()> {
-        assert_eq!(expected, process(input)?);
+        assert_eq!(
+            expected,
+            process_with(input, &Params::EXAMPLE)?
+        );
         Ok(())
     }
 }
@@ -1,19 +1,17 @@
+use aoc_parse::{IResult, ParseError};
 use nom::{
     bytes::complete::tag,
     character::complete::{alpha1, line_ending},
     combinator::{all_consuming, opt},
     multi::separated_list0,
     sequence::{separated_pair, terminated},
-    IResult,
 };
 use std::collections::HashMap;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, (towels, patterns)) =
-        parse(input).map_err(|err| {
-            miette::miette!("Parse error: {}", err)
-        })?;
+    let (_, (towels, patterns)) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
     let result =
         count_possible_matching_patterns_with_towels(
@@ -71,9 +69,7 @@ fn count_possible_matching_patterns_with_towels(
         .sum()
 }
 
-fn parse(
-    input: &str,
-) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
+fn parse(input: &str) -> IResult<(Vec<&str>, Vec<&str>)> {
     all_consuming(separated_pair(
         terminated(
             separated_list0(tag(", "), alpha1),
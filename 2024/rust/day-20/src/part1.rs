@@ -1,20 +1,16 @@
+use aoc_grid::{parser::grid, Grid};
+use aoc_parse::{IResult, ParseError};
 use glam::IVec2;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{line_ending, one_of},
-    combinator::all_consuming,
-    multi::{many0, separated_list0},
-    sequence::{pair, terminated},
-    IResult,
+    character::complete::line_ending,
+    combinator::{all_consuming, opt},
+    sequence::terminated,
 };
-use nom_locate::{position, LocatedSpan};
 use std::{
     collections::{HashMap, HashSet},
     iter::successors,
 };
 
-type Span<'a> = LocatedSpan<&'a str>;
-
 const DIRECTIONS: [IVec2; 4] =
     [IVec2::NEG_Y, IVec2::NEG_X, IVec2::Y, IVec2::X];
 const SHORTCUTS: [IVec2; 8] = [
@@ -30,46 +26,15 @@ const SHORTCUTS: [IVec2; 8] = [
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, map) =
-        parse(Span::new(input)).map_err(|err| {
-            miette::miette!("Parse error: {}", err)
-        })?;
+    let (_, map) = parse(input)
+        .map_err(|err| ParseError::new(input, err))?;
 
-    let start = map
-        .iter()
-        .find_map(|(pos, ch)| {
-            if *ch == 'S' {
-                Some(*pos)
-            } else {
-                None
-            }
-        })
-        .ok_or_else(|| {
-            miette::miette!("Cannot find 'S' in map")
-        })?;
-    let end = map
-        .iter()
-        .find_map(|(pos, ch)| {
-            if *ch == 'E' {
-                Some(*pos)
-            } else {
-                None
-            }
-        })
-        .ok_or_else(|| {
-            miette::miette!("Cannot find 'E' in map")
-        })?;
-
-    let walls = map
-        .iter()
-        .filter_map(|(pos, ch)| {
-            if *ch == '#' {
-                Some(*pos)
-            } else {
-                None
-            }
-        })
-        .collect::<HashSet<_>>();
+    let start = map.find('S').ok_or_else(|| {
+        miette::miette!("Cannot find 'S' in map")
+    })?;
+    let end = map.find('E').ok_or_else(|| {
+        miette::miette!("Cannot find 'E' in map")
+    })?;
 
     let mut visited = HashSet::new();
     let normal_path = successors(Some(start), |pos| {
@@ -80,7 +45,7 @@ pub fn process(input: &str) -> miette::Result<String> {
 
         DIRECTIONS.into_iter().find_map(|dir| {
             let next_pos = dir + *pos;
-            if !walls.contains(&next_pos)
+            if map.get(next_pos) != Some(&'#')
                 && !visited.contains(&next_pos)
             {
                 Some(next_pos)
@@ -138,34 +103,16 @@ pub fn process(input: &str) -> miette::Result<String> {
         .filter(|(_s, g)| *g >= 100)
         .count();
 
-    println!("Shortcuts: {:?}", shortcuts);
+    tracing::debug!(shortcuts, "shortcuts saving at least 100");
 
     Ok(result.to_string())
 }
 
-fn parse(input: Span) -> IResult<Span, Vec<(IVec2, char)>> {
-    let (input, map) = all_consuming(separated_list0(
-        line_ending,
-        many0(terminated(
-            pair(pos_parse, one_of("#SE")),
-            many0(tag(".")),
-        )),
-    ))(input)?;
-
-    Ok((
-        input,
-        map.into_iter().flatten().collect(),
-    ))
-}
-
-fn pos_parse(input: Span) -> IResult<Span, IVec2> {
-    let (input, position) = position(input)?;
-    let pos = IVec2::new(
-        position.location_line() as i32 - 1,
-        position.get_column() as i32 - 1,
-    );
-
-    Ok((input, pos))
+fn parse(input: &str) -> IResult<Grid<char>> {
+    all_consuming(terminated(
+        grid(|ch| "#.SE".contains(ch).then_some(ch)),
+        opt(line_ending),
+    ))(input)
 }
 
 #[cfg(test)]
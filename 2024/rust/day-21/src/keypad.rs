@@ -0,0 +1,696 @@
+//! Keypad layouts and the chain of robots typing
+//! on them.
+//!
+//! A layout is described by its rows of keys, a
+//! space being a gap:
+//!
+//! ```text
+//!  ^A
+//! <v>
+//! ```
+//!
+//! A [`Chain`] lists the keypads from the one the
+//! human presses to the one the code is typed on,
+//! each of them but the first being pressed by a
+//! robot whose arm is directed from the previous
+//! keypad. Every keypad directing a robot only
+//! has `A` and move keys: `^`, `v`, `<`, `>` and
+//! the diagonals `↖`, `↗`, `↙`, `↘`.
+use crate::lengths::{Count, LengthError, Lengths};
+use glam::IVec2;
+use miette::Diagnostic;
+use std::{
+    collections::{HashMap, VecDeque},
+    iter,
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Numeric keypad of the door.
+pub const NUMPAD: &str = "789\n456\n123\n 0A";
+/// Directional keypad of the robots.
+pub const DIRPAD: &str = " ^A\n<v>";
+
+/// Shortest paths between the keys of a keypad,
+/// missing for the keys that are not connected.
+pub type Paths = HashMap<(char, char), Vec<String>>;
+
+/// Keys moving a robot arm, with their direction.
+const MOVES: [(char, IVec2); 8] = [
+    ('^', IVec2::NEG_Y),
+    ('v', IVec2::Y),
+    ('<', IVec2::NEG_X),
+    ('>', IVec2::X),
+    ('↖', IVec2::new(-1, -1)),
+    ('↗', IVec2::new(1, -1)),
+    ('↙', IVec2::new(-1, 1)),
+    ('↘', IVec2::new(1, 1)),
+];
+
+fn direction(key: char) -> Option<IVec2> {
+    MOVES
+        .iter()
+        .find(|(move_key, _)| *move_key == key)
+        .map(|(_, direction)| *direction)
+}
+
+#[derive(
+    Debug, Clone, Error, Diagnostic, PartialEq, Eq,
+)]
+pub enum LayoutError {
+    #[error("keypad {layer} has no key")]
+    Empty { layer: usize },
+    #[error("key `{key}` appears twice on keypad {layer}")]
+    Duplicate { layer: usize, key: char },
+    #[error("a chain needs at least two keypads")]
+    #[diagnostic(help(
+        "the first keypad is the one the human presses"
+    ))]
+    TooShort,
+    #[error(
+        "keypad {layer} directs a robot but has no `A` \
+         key"
+    )]
+    NoActivate { layer: usize },
+    #[error(
+        "keypad {layer} directs a robot but `{key}` is \
+         neither `A` nor a move"
+    )]
+    #[diagnostic(help(
+        "the moves are `^`, `v`, `<`, `>`, `↖`, `↗`, \
+         `↙` and `↘`"
+    ))]
+    UnknownKey { layer: usize, key: char },
+}
+
+/// Failure while replaying presses through a
+/// chain.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum SimulationError {
+    #[error(
+        "press {press} `{key}` is not a key of the \
+         first keypad"
+    )]
+    InvalidKey { press: usize, key: char },
+    #[error(
+        "robot {robot} panics on press {press}: its arm \
+         points at the gap at {position}"
+    )]
+    Panic {
+        press: usize,
+        /// 0 is the robot the human directs, the
+        /// last one types the code
+        robot: usize,
+        position: IVec2,
+    },
+}
+
+/// Keys of a keypad by position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    by_char: HashMap<char, IVec2>,
+    by_pos: HashMap<IVec2, char>,
+    start: char,
+}
+
+impl FromStr for Layout {
+    type Err = LayoutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Layout::parse(s, 0)
+    }
+}
+
+impl Layout {
+    fn parse(
+        text: &str,
+        layer: usize,
+    ) -> Result<Self, LayoutError> {
+        let mut by_char = HashMap::new();
+        let mut by_pos = HashMap::new();
+        let mut first = None;
+        for (row, line) in text.lines().enumerate() {
+            for (column, key) in line.chars().enumerate() {
+                if key == ' ' {
+                    continue;
+                }
+                let position =
+                    IVec2::new(column as i32, row as i32);
+                if by_char.insert(key, position).is_some() {
+                    return Err(LayoutError::Duplicate {
+                        layer,
+                        key,
+                    });
+                }
+                by_pos.insert(position, key);
+                first.get_or_insert(key);
+            }
+        }
+
+        let first =
+            first.ok_or(LayoutError::Empty { layer })?;
+        Ok(Layout {
+            start: if by_char.contains_key(&'A') {
+                'A'
+            } else {
+                first
+            },
+            by_char,
+            by_pos,
+        })
+    }
+
+    pub fn numpad() -> Self {
+        NUMPAD.parse().expect("valid numeric keypad")
+    }
+
+    pub fn dirpad() -> Self {
+        DIRPAD.parse().expect("valid directional keypad")
+    }
+
+    /// Keys in order.
+    pub fn keys(&self) -> Vec<char> {
+        let mut keys = self
+            .by_char
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// Key an arm starts on, `A` or else the
+    /// first key in reading order.
+    pub fn start(&self) -> char {
+        self.start
+    }
+
+    pub fn position(&self, key: char) -> Option<IVec2> {
+        self.by_char.get(&key).copied()
+    }
+
+    pub fn key(&self, position: IVec2) -> Option<char> {
+        self.by_pos.get(&position).copied()
+    }
+
+    /// Move keys of this keypad, in a stable
+    /// order.
+    pub(crate) fn moves(&self) -> Vec<(char, IVec2)> {
+        MOVES
+            .into_iter()
+            .filter(|(key, _)| {
+                self.by_char.contains_key(key)
+            })
+            .collect()
+    }
+
+    /// Shortest paths between every two keys,
+    /// using `moves`.
+    pub(crate) fn all_paths(
+        &self,
+        moves: &[(char, IVec2)],
+    ) -> Paths {
+        let keys = self.keys();
+        keys.iter()
+            .flat_map(|&from| {
+                keys.iter().map(move |&to| {
+                    (
+                        (from, to),
+                        self.shortest_paths(
+                            from, to, moves,
+                        ),
+                    )
+                })
+            })
+            .filter(|(_, paths)| !paths.is_empty())
+            .collect()
+    }
+
+    /// Every shortest way to move an arm from
+    /// `from` to `to` and press it, using
+    /// `moves`, in lexicographic order.
+    fn shortest_paths(
+        &self,
+        from: char,
+        to: char,
+        moves: &[(char, IVec2)],
+    ) -> Vec<String> {
+        let (Some(start), Some(end)) =
+            (self.position(from), self.position(to))
+        else {
+            return vec![];
+        };
+
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(position) = queue.pop_front() {
+            for (_, direction) in moves {
+                let next = position + *direction;
+                if self.key(next).is_some()
+                    && !distances.contains_key(&next)
+                {
+                    distances.insert(
+                        next,
+                        distances[&position] + 1,
+                    );
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if !distances.contains_key(&end) {
+            return vec![];
+        }
+
+        // walk back from the end along decreasing
+        // distances
+        let distances = &distances;
+        let mut paths = vec![(end, String::from("A"))];
+        while paths
+            .iter()
+            .any(|(position, _)| *position != start)
+        {
+            paths = paths
+                .into_iter()
+                .flat_map(|(position, path)| {
+                    let distance = distances[&position];
+                    moves.iter().filter_map(
+                        move |(key, direction)| {
+                            let previous =
+                                position - *direction;
+                            (distances.get(&previous)
+                                == Some(&(distance - 1)))
+                            .then(|| {
+                                (
+                                    previous,
+                                    format!("{key}{path}"),
+                                )
+                            })
+                        },
+                    )
+                })
+                .collect();
+        }
+
+        let mut paths = paths
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+}
+
+/// Keypads from the human's to the one the code
+/// is typed on, with the shortest paths between
+/// the keys of each of them.
+#[derive(Debug, Clone)]
+pub struct Chain {
+    layouts: Vec<Layout>,
+    /// shortest paths on each keypad, using the
+    /// moves of the previous one
+    paths: Vec<Paths>,
+}
+
+impl FromStr for Chain {
+    type Err = LayoutError;
+
+    /// Layouts separated by blank lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks = vec![String::new()];
+        for line in s.lines() {
+            if line.trim().is_empty() {
+                blocks.push(String::new());
+            } else {
+                let block = blocks.last_mut().unwrap();
+                block.push_str(line);
+                block.push('\n');
+            }
+        }
+        let layouts = blocks
+            .iter()
+            .filter(|block| !block.is_empty())
+            .enumerate()
+            .map(|(layer, block)| {
+                Layout::parse(block, layer)
+            })
+            .collect::<Result<_, _>>()?;
+        Chain::new(layouts)
+    }
+}
+
+impl Chain {
+    /// Checks every keypad but the last can
+    /// direct a robot.
+    pub fn new(
+        layouts: Vec<Layout>,
+    ) -> Result<Self, LayoutError> {
+        if layouts.len() < 2 {
+            return Err(LayoutError::TooShort);
+        }
+        for (layer, layout) in
+            layouts[..layouts.len() - 1].iter().enumerate()
+        {
+            if layout.position('A').is_none() {
+                return Err(LayoutError::NoActivate {
+                    layer,
+                });
+            }
+            if let Some(key) =
+                layout.keys().into_iter().find(|key| {
+                    *key != 'A' && direction(*key).is_none()
+                })
+            {
+                return Err(LayoutError::UnknownKey {
+                    layer,
+                    key,
+                });
+            }
+        }
+
+        let paths = iter::once(Paths::new())
+            .chain(layouts.windows(2).map(|pair| {
+                pair[1].all_paths(&pair[0].moves())
+            }))
+            .collect();
+        Ok(Chain { layouts, paths })
+    }
+
+    /// The puzzle's chain: the human's
+    /// directional keypad, `robots` robots
+    /// using directional keypads and the one
+    /// at the numeric keypad.
+    pub fn standard(robots: usize) -> Self {
+        let mut layouts =
+            vec![Layout::dirpad(); robots + 1];
+        layouts.push(Layout::numpad());
+        Chain::new(layouts).expect("valid standard chain")
+    }
+
+    pub fn layouts(&self) -> &[Layout] {
+        &self.layouts
+    }
+
+    /// Lengths of every keypad, computed one by
+    /// one.
+    pub(crate) fn tables<T: Count>(
+        &self,
+    ) -> Result<Vec<Lengths<T>>, LengthError> {
+        let mut tables =
+            vec![Lengths::human(&self.layouts[0])];
+        for layer in 1..self.layouts.len() {
+            let (next, _) = tables[layer - 1].next(
+                &self.layouts[layer],
+                &self.paths[layer],
+            )?;
+            tables.push(next);
+        }
+        Ok(tables)
+    }
+
+    /// Lengths of the last keypad, runs of
+    /// identical keypads being added at once.
+    pub fn lengths<T: Count>(
+        &self,
+    ) -> Result<Lengths<T>, LengthError> {
+        let layouts = &self.layouts;
+        let mut lengths = Lengths::human(&layouts[0]);
+        let mut layer = 1;
+        while layer < layouts.len() {
+            // same keypad directed by the same one
+            let run = (layer..layouts.len())
+                .take_while(|&next| {
+                    layouts[next] == layouts[layer]
+                        && layouts[next - 1]
+                            == layouts[layer - 1]
+                })
+                .count();
+            lengths = lengths.repeat(
+                &layouts[layer],
+                &self.paths[layer],
+                run,
+            )?;
+            layer += run;
+        }
+        Ok(lengths)
+    }
+
+    fn expand(
+        &self,
+        tables: &[Lengths<u64>],
+        layer: usize,
+        code: &str,
+    ) -> String {
+        if layer == 0 {
+            return code.to_string();
+        }
+        let mut from = self.layouts[layer].start();
+        code.chars()
+            .map(|to| {
+                // the cheapest path one keypad further,
+                // the first in order for a stable result
+                let path = self.paths[layer][&(from, to)]
+                    .iter()
+                    .filter_map(|path| {
+                        let length = tables[layer - 1]
+                            .path_len(path)
+                            .ok()??;
+                        Some((length, path))
+                    })
+                    .min_by_key(|(length, _)| *length)
+                    .map(|(_, path)| path)
+                    .expect("keys are connected");
+                from = to;
+                self.expand(tables, layer - 1, path)
+            })
+            .collect()
+    }
+
+    /// One of the shortest sequences of presses
+    /// of the human typing `code` on the last
+    /// keypad.
+    ///
+    /// Its length grows exponentially with the
+    /// number of keypads, only use it for small
+    /// chains.
+    pub fn shortest_sequence(
+        &self,
+        code: &str,
+    ) -> Result<String, LengthError> {
+        let tables = self.tables::<u64>()?;
+        // fails if a key can not be reached
+        tables.last().unwrap().len(code)?;
+        Ok(self.expand(&tables, tables.len() - 1, code))
+    }
+
+    /// Replays the presses of the human through
+    /// the robots, returning what is typed on
+    /// the last keypad.
+    pub fn simulate(
+        &self,
+        presses: &str,
+    ) -> Result<String, SimulationError> {
+        let robots = &self.layouts[1..];
+        // every arm starts on its start key
+        let mut arms = robots
+            .iter()
+            .map(|layout| {
+                layout.position(layout.start()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let mut typed = String::new();
+        for (press, mut key) in presses.chars().enumerate()
+        {
+            if self.layouts[0].position(key).is_none() {
+                return Err(SimulationError::InvalidKey {
+                    press,
+                    key,
+                });
+            }
+            for (robot, layout) in robots.iter().enumerate()
+            {
+                if key == 'A' {
+                    // pass the press on to the next robot
+                    key = layout
+                        .key(arms[robot])
+                        .expect("arms stay on keys");
+                    if robot == robots.len() - 1 {
+                        typed.push(key);
+                    }
+                    continue;
+                }
+
+                arms[robot] += direction(key)
+                    .expect("directing keypads only move");
+                if layout.key(arms[robot]).is_none() {
+                    return Err(SimulationError::Panic {
+                        press,
+                        robot,
+                        position: arms[robot],
+                    });
+                }
+                break;
+            }
+        }
+
+        Ok(typed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("029A", 68)]
+    #[case("980A", 60)]
+    #[case("179A", 68)]
+    #[case("456A", 64)]
+    #[case("379A", 64)]
+    fn test_shortest_sequence(
+        #[case] code: &str,
+        #[case] expected: usize,
+    ) {
+        for robots in 0..=2 {
+            let chain = Chain::standard(robots);
+            let presses =
+                chain.shortest_sequence(code).unwrap();
+            assert_eq!(
+                Ok(presses.len() as u64),
+                chain.lengths().unwrap().len(code)
+            );
+            assert_eq!(
+                Ok(code.to_string()),
+                chain.simulate(&presses)
+            );
+        }
+        assert_eq!(
+            expected,
+            Chain::standard(2)
+                .shortest_sequence(code)
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_simulate() {
+        assert_eq!(
+            Ok("029A".to_string()),
+            Chain::standard(2).simulate(
+                "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>\
+                 AAvA^A<v<A>A>^AAAvA<^A>A",
+            )
+        );
+        // left of `0` on the numeric keypad
+        assert_eq!(
+            Err(SimulationError::Panic {
+                press: 1,
+                robot: 0,
+                position: IVec2::new(0, 3),
+            }),
+            Chain::standard(0).simulate("<<")
+        );
+        // left of `^` on the directional keypad
+        assert_eq!(
+            Err(SimulationError::Panic {
+                press: 4,
+                robot: 0,
+                position: IVec2::new(0, 0),
+            }),
+            Chain::standard(1).simulate("<A><<")
+        );
+        assert_eq!(
+            Err(SimulationError::InvalidKey {
+                press: 1,
+                key: 'x'
+            }),
+            Chain::standard(2).simulate("<x")
+        );
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let numpad = NUMPAD.parse::<Layout>().unwrap();
+        let dirpad = DIRPAD.parse::<Layout>().unwrap();
+        assert_eq!(
+            vec!["<^<A", "^<<A"],
+            numpad.shortest_paths(
+                'A',
+                '1',
+                &dirpad.moves()
+            )
+        );
+        assert_eq!(
+            vec!["A"],
+            numpad.shortest_paths(
+                '5',
+                '5',
+                &dirpad.moves()
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("", LayoutError::TooShort)]
+    #[case(DIRPAD, LayoutError::TooShort)]
+    #[case(" ^A\n<v^\n\n123", LayoutError::Duplicate { layer: 0, key: '^' })]
+    #[case(" ^B\n<v>\n\n123", LayoutError::NoActivate { layer: 0 })]
+    #[case(" ^A\n<v>\n\n12A\n\nAB", LayoutError::UnknownKey { layer: 1, key: '1' })]
+    fn test_invalid_chain(
+        #[case] text: &str,
+        #[case] expected: LayoutError,
+    ) {
+        assert_eq!(
+            Err(expected),
+            text.parse::<Chain>().map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_phone_numpad() {
+        // no `A`, the arm starts on `1`
+        let chain = format!(
+            "{DIRPAD}\n\n{DIRPAD}\n\n123\n456\n789\n*0#"
+        )
+        .parse::<Chain>()
+        .unwrap();
+        let presses =
+            chain.shortest_sequence("0#1").unwrap();
+        assert_eq!(
+            Ok("0#1".to_string()),
+            chain.simulate(&presses)
+        );
+        assert_eq!(
+            presses.len(),
+            chain
+                .lengths::<usize>()
+                .unwrap()
+                .len("0#1")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_diagonal_dirpad() {
+        let diagonal = "↖^↗\n<A>\n↙v↘";
+        let standard = Chain::standard(1)
+            .lengths::<usize>()
+            .unwrap()
+            .len("159A")
+            .unwrap();
+        let chain =
+            format!("{diagonal}\n\n{diagonal}\n\n{NUMPAD}")
+                .parse::<Chain>()
+                .unwrap();
+        let presses =
+            chain.shortest_sequence("159A").unwrap();
+        assert_eq!(
+            Ok("159A".to_string()),
+            chain.simulate(&presses)
+        );
+        assert!(presses.len() < standard, "{presses}");
+    }
+}
@@ -0,0 +1,580 @@
+//! Lengths of the shortest sequences of presses,
+//! counted in any integer type with overflow
+//! checks.
+//!
+//! The length of moving the arm of a keypad
+//! between two keys and pressing it is the length
+//! of its cheapest path typed one keypad closer
+//! to the human, a sum of lengths of that keypad.
+//! Once the cheapest paths of identical keypads
+//! stop changing, going one keypad further is a
+//! linear map of the lengths, applied many times
+//! by raising its matrix to a power.
+use crate::keypad::{Layout, Paths};
+use miette::Diagnostic;
+use num_traits::{
+    CheckedAdd, CheckedMul, FromPrimitive, One, Zero,
+};
+use std::{
+    any::type_name,
+    collections::HashMap,
+    fmt::{Debug, Display},
+};
+use thiserror::Error;
+
+/// Counter of presses.
+pub trait Count:
+    Clone
+    + Ord
+    + Debug
+    + Display
+    + Zero
+    + One
+    + CheckedAdd
+    + CheckedMul
+    + FromPrimitive
+{
+}
+
+impl<T> Count for T where
+    T: Clone
+        + Ord
+        + Debug
+        + Display
+        + Zero
+        + One
+        + CheckedAdd
+        + CheckedMul
+        + FromPrimitive
+{
+}
+
+#[derive(
+    Debug, Clone, Error, Diagnostic, PartialEq, Eq,
+)]
+pub enum LengthError {
+    #[error(
+        "lengths on keypad {layer} overflow a `{counter}`"
+    )]
+    #[diagnostic(
+        code(day_21::overflow),
+        help(
+            "use a wider counter with `--counter u128` \
+             or `--counter bigint`"
+        )
+    )]
+    Overflow { layer: usize, counter: &'static str },
+    #[error(
+        "the complexity of `{code}` overflows a \
+         `{counter}`"
+    )]
+    #[diagnostic(
+        code(day_21::overflow),
+        help(
+            "use a wider counter with `--counter u128` \
+             or `--counter bigint`"
+        )
+    )]
+    ComplexityOverflow {
+        code: String,
+        counter: &'static str,
+    },
+    #[error(
+        "no sequence moves the arm of keypad {layer} \
+         from `{from}` to `{to}`"
+    )]
+    #[diagnostic(code(day_21::unreachable))]
+    Unreachable { layer: usize, from: char, to: char },
+}
+
+/// Index of the cheapest path of every pair of
+/// keys of a keypad.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Choices {
+    paths: HashMap<(char, char), usize>,
+    /// whether another path is as cheap for some
+    /// pair, which may make the choice change
+    /// later
+    tied: bool,
+}
+
+/// Presses of the human moving the arm of a
+/// keypad between two keys and pressing it,
+/// missing for the keys it can not move between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lengths<T> {
+    layer: usize,
+    start: char,
+    pairs: HashMap<(char, char), T>,
+}
+
+impl<T: Count> Lengths<T> {
+    /// The human presses every key once.
+    pub fn human(layout: &Layout) -> Self {
+        let keys = layout.keys();
+        Lengths {
+            layer: 0,
+            start: layout.start(),
+            pairs: keys
+                .iter()
+                .flat_map(|&from| {
+                    keys.iter().map(move |&to| {
+                        ((from, to), T::one())
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    fn overflow(&self) -> LengthError {
+        LengthError::Overflow {
+            layer: self.layer,
+            counter: type_name::<T>(),
+        }
+    }
+
+    /// Presses of typing `path` on this keypad,
+    /// `None` when a pair can not be typed.
+    pub(crate) fn path_len(
+        &self,
+        path: &str,
+    ) -> Result<Option<T>, LengthError> {
+        let mut from = self.start;
+        let mut length = T::zero();
+        for to in path.chars() {
+            let Some(pair) = self.pairs.get(&(from, to))
+            else {
+                return Ok(None);
+            };
+            length = length
+                .checked_add(pair)
+                .ok_or_else(|| self.overflow())?;
+            from = to;
+        }
+        Ok(Some(length))
+    }
+
+    /// Presses of the human typing `code` on this
+    /// keypad.
+    pub fn len(
+        &self,
+        code: &str,
+    ) -> Result<T, LengthError> {
+        let mut from = self.start;
+        let mut length = T::zero();
+        for to in code.chars() {
+            let pair = self.pairs.get(&(from, to)).ok_or(
+                LengthError::Unreachable {
+                    layer: self.layer,
+                    from,
+                    to,
+                },
+            )?;
+            length = length
+                .checked_add(pair)
+                .ok_or_else(|| self.overflow())?;
+            from = to;
+        }
+        Ok(length)
+    }
+
+    /// Lengths of the next keypad, whose `paths`
+    /// use the keys of this one, with the index
+    /// of the path chosen for every pair.
+    pub(crate) fn next(
+        &self,
+        layout: &Layout,
+        paths: &Paths,
+    ) -> Result<(Self, Choices), LengthError> {
+        let mut pairs = HashMap::new();
+        let mut choices = Choices {
+            paths: HashMap::new(),
+            tied: false,
+        };
+        for (&pair, paths) in paths {
+            let mut best: Option<(usize, T)> = None;
+            for (index, path) in paths.iter().enumerate() {
+                let Some(length) = self.path_len(path)?
+                else {
+                    continue;
+                };
+                // the first path wins ties
+                match &best {
+                    Some((_, best)) if length == *best => {
+                        choices.tied = true;
+                    }
+                    Some((_, best)) if length > *best => {}
+                    _ => best = Some((index, length)),
+                }
+            }
+            if let Some((index, length)) = best {
+                choices.paths.insert(pair, index);
+                pairs.insert(pair, length);
+            }
+        }
+        let next = Lengths {
+            layer: self.layer + 1,
+            start: layout.start(),
+            pairs,
+        };
+        Ok((next, choices))
+    }
+
+    /// Lengths `times` keypads further, every one
+    /// of them being `layout` with `paths`, this
+    /// one included.
+    ///
+    /// Keypads are added one by one until their
+    /// choices of paths repeat without ties, they
+    /// are then assumed to stay the same and the
+    /// remaining ones are added at once by
+    /// exponentiation. The assumption is checked
+    /// on the last keypad only: when its choices
+    /// differ, or cannot be counted, the
+    /// remaining keypads are added one by one
+    /// instead. Choices that change in between
+    /// and back are missed, the lengths are then
+    /// too long: the assumption holds for the
+    /// puzzle's keypads, not for every layout.
+    pub fn repeat(
+        self,
+        layout: &Layout,
+        paths: &Paths,
+        times: usize,
+    ) -> Result<Self, LengthError> {
+        let mut lengths = self;
+        let mut previous = None;
+        for done in 0..times {
+            let (next, choices) =
+                lengths.next(layout, paths)?;
+            lengths = next;
+            if !choices.tied
+                && previous.as_ref() == Some(&choices)
+            {
+                let remaining = times - done - 1;
+                match lengths
+                    .clone()
+                    .power(paths, &choices, remaining)
+                {
+                    Ok(powered)
+                        if powered.keeps(
+                            layout, paths, &choices,
+                        ) =>
+                    {
+                        return Ok(powered);
+                    }
+                    _ => {}
+                }
+                return lengths
+                    .chain(layout, paths, remaining);
+            }
+            previous = Some(choices);
+        }
+        Ok(lengths)
+    }
+
+    /// Lengths `times` keypads further, adding
+    /// them one by one.
+    fn chain(
+        self,
+        layout: &Layout,
+        paths: &Paths,
+        times: usize,
+    ) -> Result<Self, LengthError> {
+        (0..times).try_fold(self, |lengths, _| {
+            lengths
+                .next(layout, paths)
+                .map(|(next, _)| next)
+        })
+    }
+
+    /// Whether the next keypad would still go
+    /// through the paths in `choices`.
+    fn keeps(
+        &self,
+        layout: &Layout,
+        paths: &Paths,
+        choices: &Choices,
+    ) -> bool {
+        self.next(layout, paths).is_ok_and(|(_, next)| {
+            next.paths == choices.paths
+        })
+    }
+
+    /// Applies `times` keypads going through the
+    /// paths in `choices`.
+    fn power(
+        self,
+        paths: &Paths,
+        choices: &Choices,
+        times: usize,
+    ) -> Result<Self, LengthError> {
+        if times == 0 {
+            return Ok(self);
+        }
+        let mut pairs =
+            self.pairs.keys().copied().collect::<Vec<_>>();
+        pairs.sort();
+        let index = pairs
+            .iter()
+            .enumerate()
+            .map(|(index, pair)| (*pair, index))
+            .collect::<HashMap<_, _>>();
+
+        // how many times each pair is gone through
+        // one keypad closer to the human
+        let mut matrix =
+            vec![vec![T::zero(); pairs.len()]; pairs.len()];
+        for (row, pair) in pairs.iter().enumerate() {
+            let path = &paths[pair][choices.paths[pair]];
+            let mut from = self.start;
+            for to in path.chars() {
+                let column = index[&(from, to)];
+                matrix[row][column] = matrix[row][column]
+                    .checked_add(&T::one())
+                    .ok_or_else(|| self.overflow())?;
+                from = to;
+            }
+        }
+
+        let layer = self.layer + times;
+        let overflow = || LengthError::Overflow {
+            layer,
+            counter: type_name::<T>(),
+        };
+        let mut lengths = pairs
+            .iter()
+            .map(|pair| self.pairs[pair].clone())
+            .collect::<Vec<_>>();
+        let mut times = times;
+        loop {
+            if times & 1 == 1 {
+                lengths = multiply(&matrix, &lengths)
+                    .ok_or_else(overflow)?;
+            }
+            times >>= 1;
+            if times == 0 {
+                break;
+            }
+            matrix =
+                square(&matrix).ok_or_else(overflow)?;
+        }
+
+        Ok(Lengths {
+            layer,
+            start: self.start,
+            pairs: pairs.into_iter().zip(lengths).collect(),
+        })
+    }
+}
+
+fn dot<T: Count>(
+    row: &[T],
+    column: impl Iterator<Item = T>,
+) -> Option<T> {
+    row.iter()
+        .zip(column)
+        .try_fold(T::zero(), |sum, (a, b)| {
+            sum.checked_add(&a.checked_mul(&b)?)
+        })
+}
+
+fn multiply<T: Count>(
+    matrix: &[Vec<T>],
+    vector: &[T],
+) -> Option<Vec<T>> {
+    matrix
+        .iter()
+        .map(|row| dot(row, vector.iter().cloned()))
+        .collect()
+}
+
+fn square<T: Count>(
+    matrix: &[Vec<T>],
+) -> Option<Vec<Vec<T>>> {
+    matrix
+        .iter()
+        .map(|row| {
+            (0..matrix.len())
+                .map(|column| {
+                    dot(
+                        row,
+                        matrix
+                            .iter()
+                            .map(|row| row[column].clone()),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Lengths of the numeric keypad of the puzzle's
+/// chain with `robots` robots using directional
+/// keypads, without building every keypad.
+///
+/// The directional keypads are added as in
+/// [`Lengths::repeat`], assuming the cheapest
+/// paths stay the same once they repeat.
+pub fn standard<T: Count>(
+    robots: usize,
+) -> Result<Lengths<T>, LengthError> {
+    let dirpad = Layout::dirpad();
+    let numpad = Layout::numpad();
+    let moves = dirpad.moves();
+    let dirpad_paths = dirpad.all_paths(&moves);
+    let numpad_paths = numpad.all_paths(&moves);
+
+    let (lengths, _) = Lengths::human(&dirpad)
+        .repeat(&dirpad, &dirpad_paths, robots)?
+        .next(&numpad, &numpad_paths)?;
+    Ok(lengths)
+}
+
+/// Sum of the lengths of `codes` times their
+/// numeric part.
+pub fn complexity<T: Count>(
+    lengths: &Lengths<T>,
+    codes: &[&str],
+) -> Result<T, LengthError> {
+    codes.iter().try_fold(T::zero(), |sum, &code| {
+        let overflow = || LengthError::ComplexityOverflow {
+            code: code.to_string(),
+            counter: type_name::<T>(),
+        };
+        let value = T::from_usize(code_val(code))
+            .ok_or_else(overflow)?;
+        lengths
+            .len(code)?
+            .checked_mul(&value)
+            .and_then(|complexity| {
+                sum.checked_add(&complexity)
+            })
+            .ok_or_else(overflow)
+    })
+}
+
+fn code_val(code: &str) -> usize {
+    let first_non_digit = code
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(code.len());
+
+    code[..first_non_digit].parse::<usize>().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypad::{Chain, Layout};
+    use num_bigint::BigUint;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("029A", 29)]
+    #[case("980A", 980)]
+    #[case("179A", 179)]
+    #[case("456A", 456)]
+    #[case("379A", 379)]
+    fn test_code_val(
+        #[case] input: &str,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(expected, code_val(input));
+    }
+
+    #[test]
+    fn test_power_same_as_chain() {
+        for robots in 0..=40 {
+            // every keypad added one by one
+            let mut tables = Chain::standard(robots)
+                .tables::<u128>()
+                .unwrap();
+            let expected = tables.pop().unwrap();
+            let lengths = standard::<u128>(robots).unwrap();
+            for code in
+                ["029A", "980A", "179A", "456A", "379A"]
+            {
+                assert_eq!(
+                    expected.len(code),
+                    lengths.len(code),
+                    "{robots} robots, {code}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_power_falls_back() {
+        // the cheapest paths repeat on the 4th and
+        // 5th keypads, then change for good
+        let layout =
+            "A↗ ^\n<↖>v".parse::<Layout>().unwrap();
+        for keypads in 2..=30 {
+            let chain =
+                Chain::new(vec![layout.clone(); keypads])
+                    .unwrap();
+            let expected =
+                chain.tables::<u128>().unwrap().pop();
+            assert_eq!(
+                expected,
+                chain.lengths::<u128>().ok(),
+                "{keypads} keypads"
+            );
+        }
+    }
+
+    #[test]
+    fn test_counters() {
+        assert_eq!(
+            Ok(82050061710),
+            standard::<u64>(25).unwrap().len("029A")
+        );
+        assert_eq!(
+            Ok(16809864843533678156),
+            standard::<u64>(46).unwrap().len("029A")
+        );
+        assert_eq!(
+            Err(LengthError::Overflow {
+                layer: 47,
+                counter: "u64"
+            }),
+            standard::<u64>(47).map(|_| ())
+        );
+        assert!(
+            standard::<u128>(47)
+                .unwrap()
+                .len("029A")
+                .unwrap()
+                > u64::MAX as u128
+        );
+        let wide = standard::<u128>(80)
+            .unwrap()
+            .len("029A")
+            .unwrap();
+        let big = standard::<BigUint>(80)
+            .unwrap()
+            .len("029A")
+            .unwrap();
+        assert_eq!(wide.to_string(), big.to_string());
+        assert!(standard::<BigUint>(10_000)
+            .unwrap()
+            .len("029A")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_complexity_overflow() {
+        let lengths = standard::<u64>(40).unwrap();
+        assert_eq!(
+            Err(LengthError::ComplexityOverflow {
+                code: "980A".to_string(),
+                counter: "u64"
+            }),
+            complexity(&lengths, &["029A", "980A"])
+        );
+    }
+}
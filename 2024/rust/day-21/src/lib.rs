@@ -1,2 +1,47 @@
+use std::path::PathBuf;
+
+pub mod keypad;
+pub mod lengths;
 pub mod part1;
 pub mod part2;
+
+/// Parameters of part 2, the defaults are the
+/// real puzzle's.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// number of robots using a directional
+    /// keypad between you and the numeric
+    /// keypad
+    #[clap(long, default_value_t = Params::default().robots)]
+    pub robots: usize,
+    /// file of keypad layouts separated by blank
+    /// lines, from yours to the one the codes are
+    /// typed on, replacing the puzzle's chain and
+    /// `--robots`
+    #[clap(long)]
+    pub keypads: Option<PathBuf>,
+    /// integer type the lengths are counted in
+    #[clap(long, value_enum, default_value_t = Params::default().counter)]
+    pub counter: Counter,
+}
+
+/// Integer type counting presses, wider ones
+/// allowing more robots.
+#[derive(
+    clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Counter {
+    U64,
+    U128,
+    Bigint,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            robots: 25,
+            keypads: None,
+            counter: Counter::U64,
+        }
+    }
+}
@@ -1,335 +1,68 @@
-use glam::IVec2;
-use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    sync::OnceLock,
+use crate::{
+    keypad::Chain,
+    lengths::{self, complexity, Count, Lengths},
+    Counter, Params,
 };
+use miette::{Context, IntoDiagnostic};
+use num_bigint::BigUint;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
+    process_with(input, &Params::default())
+}
+
+#[tracing::instrument(skip(input))]
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<String> {
     let codes = parse(input).map_err(|err| {
         miette::miette!("Parse error: {}", err)
     })?;
 
-    let bots = if cfg!(test) { 2 } else { 25 };
-
-    let path_cache = &mut HashMap::new();
-    let length_cache = &mut HashMap::new();
-    let mut state = State {
-        path_cache,
-        length_cache,
+    let chain = match &params.keypads {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    format!(
+                        "reading keypads `{}`",
+                        path.display()
+                    )
+                })?
+                .parse::<Chain>()?,
+        ),
+        None => None,
     };
 
-    let result = codes
-        .iter()
-        .map(|&code| state.code_complexity(code, bots))
-        .sum::<usize>();
-
-    Ok(result.to_string())
-}
-
-struct State<'a> {
-    path_cache: &'a mut HashMap<
-        (char, char),
-        (usize, HashSet<String>),
-    >,
-    length_cache:
-        &'a mut HashMap<(char, char, usize), usize>,
-}
-
-fn code_val(code: &str) -> usize {
-    let first_non_digit = code
-        .find(|ch: char| !ch.is_digit(10))
-        .unwrap_or(code.len());
-
-    code[..first_non_digit].parse::<usize>().unwrap_or(0)
-}
-
-impl<'a> State<'a> {
-    fn code_complexity(
-        &mut self,
-        code: &str,
-        depth: usize,
-    ) -> usize {
-        self.shortest_seq_len(code, depth) * code_val(code)
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Keypad {
-    Numpad,
-    Dirpad,
-}
-
-static NUMPAD_POSITIONS: OnceLock<(
-    HashMap<char, IVec2>,
-    HashMap<IVec2, char>,
-)> = OnceLock::new();
-static DIRPAD_POSITIONS: OnceLock<(
-    HashMap<char, IVec2>,
-    HashMap<IVec2, char>,
-)> = OnceLock::new();
-
-fn to_position(
-    positions: &[&str],
-) -> (
-    HashMap<char, IVec2>,
-    HashMap<IVec2, char>,
-) {
-    let by_char = positions
-        .into_iter()
-        .enumerate()
-        .flat_map(|(row, s)| {
-            s.chars().enumerate().filter_map(
-                move |(column, ch)| {
-                    ch.ne(&' ').then_some((
-                        ch,
-                        IVec2::new(
-                            column as i32,
-                            row as i32,
-                        ),
-                    ))
-                },
-            )
-        })
-        .collect::<HashMap<_, _>>();
-
-    let by_pos = by_char
-        .iter()
-        .map(|(ch, pos)| (*pos, *ch))
-        .collect();
-
-    (by_char, by_pos)
-}
-
-impl Keypad {
-    fn keypad_buttons(&self) -> &[&str] {
-        match self {
-            Keypad::Numpad => &["789", "456", "123", " 0A"],
-            Keypad::Dirpad => &[" ^A", "<v>"],
-        }
-    }
-
-    fn init_positions(
-        &self,
-    ) -> &(
-        HashMap<char, IVec2>,
-        HashMap<IVec2, char>,
-    ) {
-        match self {
-            Keypad::Numpad => {
-                NUMPAD_POSITIONS.get_or_init(|| {
-                    to_position(self.keypad_buttons())
-                })
-            }
-            Keypad::Dirpad => {
-                DIRPAD_POSITIONS.get_or_init(|| {
-                    to_position(self.keypad_buttons())
-                })
-            }
-        }
-    }
-
-    fn to_position(&self, ch: &char) -> Option<IVec2> {
-        let (by_char, _) = self.init_positions();
-
-        by_char.get(ch).copied()
-    }
-
-    fn to_char(&self, pos: &IVec2) -> Option<char> {
-        let (_, by_pos) = self.init_positions();
-
-        by_pos.get(pos).copied()
-    }
-
-    fn shortest_paths(
-        &self,
-        cache: &mut HashMap<
-            (char, char),
-            (usize, HashSet<String>),
-        >,
-        path: (char, char),
-    ) -> (usize, HashSet<String>) {
-        cache
-            .entry(path)
-            .or_insert_with(|| {
-                // Best way to go from a key to itself is
-                // just to press activate
-                if path.0 == path.1 {
-                    return (
-                        1,
-                        HashSet::from(["A".into()]),
-                    );
-                }
-
-                let mut shortest_path_len = usize::MAX;
-                let mut paths = HashSet::new();
-                let mut queue = VecDeque::new();
-                queue.push_back((
-                    self.to_position(&path.0)
-                        .expect("invalid pushed position"),
-                    String::new(),
-                ));
-                'bfs: while let Some((pos, moves)) =
-                    queue.pop_front()
-                {
-                    for (dir, key) in [
-                        (pos + IVec2::NEG_Y, '^'),
-                        (pos + IVec2::Y, 'v'),
-                        (pos + IVec2::X, '>'),
-                        (pos + IVec2::NEG_X, '<'),
-                    ] {
-                        if let Some(new_ch) =
-                            self.to_char(&dir)
-                        {
-                            if new_ch == path.1 {
-                                if shortest_path_len
-                                    < moves.len() + 2
-                                {
-                                    break 'bfs;
-                                }
-                                shortest_path_len =
-                                    moves.len() + 2;
-                                paths.insert(format!(
-                                    "{}{}A",
-                                    moves, key
-                                ));
-                            } else {
-                                queue.push_back((
-                                    dir,
-                                    format!(
-                                        "{}{}",
-                                        moves, key
-                                    ),
-                                ))
-                            }
-                        };
-                    }
-                }
-
-                (shortest_path_len, paths)
-            })
-            .clone()
-    }
-
-    fn to_shortest_seqs(
-        &self,
-        cache: &mut HashMap<
-            (char, char),
-            (usize, HashSet<String>),
-        >,
-        code: &str,
-    ) -> HashSet<String> {
-        ["A", code]
-            .into_iter()
-            .flat_map(|s| s.chars())
-            .tuple_windows::<(char, char)>()
-            .map(|(from, to)| {
-                self.shortest_paths(cache, (from, to))
-                    .1
-                    .into_iter()
-                    .collect::<Vec<String>>()
-            })
-            .multi_cartesian_product()
-            .map(|path| path.join(""))
-            .collect()
-    }
-
-    fn shortest_path_len(
-        &self,
-        path_cache: &mut HashMap<
-            (char, char),
-            (usize, HashSet<String>),
-        >,
-        length_cache: &mut HashMap<
-            (char, char, usize),
-            usize,
-        >,
-        path: (char, char),
-        depth: usize,
-    ) -> usize {
-        if let Some(length) =
-            length_cache.get(&(path.0, path.1, depth))
-        {
-            return *length;
+    let result = match params.counter {
+        Counter::U64 => {
+            total::<u64>(chain.as_ref(), params, &codes)?
         }
-
-        if depth == 1 {
-            return self.shortest_paths(path_cache, path).0;
+        Counter::U128 => {
+            total::<u128>(chain.as_ref(), params, &codes)?
         }
+        Counter::Bigint => total::<BigUint>(
+            chain.as_ref(),
+            params,
+            &codes,
+        )?,
+    };
 
-        let min_path = self
-            .shortest_paths(path_cache, path)
-            .1
-            .iter()
-            .map(|sub_path| {
-                self.shortest_seq_len(
-                    path_cache,
-                    length_cache,
-                    sub_path,
-                    depth - 1,
-                )
-            })
-            .min()
-            .unwrap_or(usize::MAX);
-
-        length_cache
-            .insert((path.0, path.1, depth), min_path);
-        min_path
-    }
-
-    fn shortest_seq_len(
-        &self,
-        path_cache: &mut HashMap<
-            (char, char),
-            (usize, HashSet<String>),
-        >,
-        length_cache: &mut HashMap<
-            (char, char, usize),
-            usize,
-        >,
-        code: &str,
-        depth: usize,
-    ) -> usize {
-        ["A", code]
-            .into_iter()
-            .flat_map(|s| s.chars())
-            .tuple_windows::<(char, char)>()
-            .map(|(from, to)| {
-                self.shortest_path_len(
-                    path_cache,
-                    length_cache,
-                    (from, to),
-                    depth,
-                )
-            })
-            .sum::<usize>()
-    }
+    Ok(result)
 }
 
-impl State<'_> {
-    fn shortest_seq_len(
-        &mut self,
-        code: &str,
-        depth: usize,
-    ) -> usize {
-        let numpad = Keypad::Numpad;
-        let seqs =
-            numpad.to_shortest_seqs(self.path_cache, code);
-
-        let dirpad = Keypad::Dirpad;
-        seqs.iter()
-            .map(|code| {
-                dirpad.shortest_seq_len(
-                    self.path_cache,
-                    self.length_cache,
-                    code,
-                    depth,
-                )
-            })
-            .min()
-            .unwrap_or(usize::MAX)
-    }
+/// Sum of the complexities counted in `T`.
+fn total<T: Count>(
+    chain: Option<&Chain>,
+    params: &Params,
+    codes: &[&str],
+) -> miette::Result<String> {
+    let lengths: Lengths<T> = match chain {
+        Some(chain) => chain.lengths()?,
+        None => lengths::standard(params.robots)?,
+    };
+    Ok(complexity(&lengths, codes)?.to_string())
 }
 
 fn parse(input: &str) -> Result<Vec<&str>, &'static str> {
@@ -342,37 +75,14 @@ mod tests {
     use rstest::rstest;
 
     #[rstest]
-    #[case("029A", 29)]
-    #[case("980A", 980)]
-    #[case("179A", 179)]
-    #[case("456A", 456)]
-    #[case("379A", 379)]
-    fn test_code_val(
-        #[case] input: &str,
-        #[case] expected: usize,
-    ) -> miette::Result<()> {
-        assert_eq!(expected, code_val(input));
-        Ok(())
-    }
-
-    #[rstest]
-    #[case("029A", 68)]
-    #[case("980A", 60)]
-    #[case("179A", 68)]
-    #[case("456A", 64)]
-    #[case("379A", 64)]
+    #[case(2, 68)]
+    #[case(25, 82050061710)]
     fn test_code_shortest_len(
-        #[case] input: &str,
-        #[case] expected: usize,
+        #[case] robots: usize,
+        #[case] expected: u64,
     ) -> miette::Result<()> {
-        let mut state = State {
-            path_cache: &mut HashMap::new(),
-            length_cache: &mut HashMap::new(),
-        };
-        assert_eq!(
-            expected,
-            state.shortest_seq_len(input, 2)
-        );
+        let lengths = Chain::standard(robots).lengths()?;
+        assert_eq!(expected, lengths.len("029A")?);
         Ok(())
     }
 
@@ -394,10 +104,46 @@ mod tests {
         #[case] input: &str,
         #[case] expected: u32,
     ) -> miette::Result<()> {
+        let params = Params {
+            robots: 2,
+            ..Params::default()
+        };
         assert_eq!(
             expected.to_string().as_str(),
-            process(input)?
+            process_with(input, &params)?
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(Counter::U64)]
+    #[case(Counter::U128)]
+    #[case(Counter::Bigint)]
+    fn test_counters(
+        #[case] counter: Counter,
+    ) -> miette::Result<()> {
+        let params = Params {
+            counter,
+            ..Params::default()
+        };
+        assert_eq!(
+            "2379451789590",
+            process_with("029A", &params)?
         );
         Ok(())
     }
+
+    #[test]
+    fn test_overflow() {
+        let params = Params {
+            robots: 60,
+            ..Params::default()
+        };
+        assert!(process_with("029A", &params).is_err());
+        let params = Params {
+            counter: Counter::U128,
+            ..params
+        };
+        assert!(process_with("029A", &params).is_ok());
+    }
 }
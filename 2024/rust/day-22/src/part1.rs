@@ -13,8 +13,7 @@ pub fn process(input: &str) -> miette::Result<String> {
                 Some(*buyer as usize),
                 compute_next_number,
             )
-            .skip(2000)
-            .next()
+            .nth(2000)
         })
         .sum::<usize>();
 
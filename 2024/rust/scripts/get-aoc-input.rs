@@ -6,17 +6,27 @@ edition = "2021"
 clap = { version = "4.2", features = ["derive"] }
 nom = "7.1.3"
 reqwest = { version = "0.11.22", features=["blocking"] }
+serde = { version = "1.0", features = ["derive"] }
+toml = "0.8"
 ---
 
-use clap::{error::ErrorKind, CommandFactory, Parser};
+use clap::{
+    error::ErrorKind, CommandFactory, Parser, Subcommand,
+};
 use nom::{
     bytes::complete::tag, character::complete,
     sequence::preceded, IResult,
 };
-use reqwest::{blocking::Client, header::COOKIE};
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use reqwest::{
+    blocking::Client, header::COOKIE, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, SystemTime},
+};
 
 #[derive(Parser, Debug)]
 #[clap(version)]
@@ -26,21 +36,505 @@ struct Args {
     /// the repo
     #[clap(short, long)]
     day: String,
+    /// puzzle year
+    #[clap(short, long, default_value_t = 2024)]
+    year: u32,
     /// a way to pass in the justfile directory
     /// so that we're always in the root without
     /// doing any shenanigans
     #[clap(long)]
     current_working_directory: PathBuf,
+    /// server to download from, point it at a
+    /// local stand-in server for tests
+    #[clap(
+        long,
+        default_value = "https://adventofcode.com"
+    )]
+    base_url: String,
+    /// where downloads are kept so they are only
+    /// requested once, defaults to `.aoc-cache`
+    /// in the working directory
+    #[clap(long)]
+    cache_dir: Option<PathBuf>,
+    #[clap(subcommand)]
+    mode: Option<Mode>,
+}
+
+#[derive(Subcommand, Debug, Clone, Default)]
+enum Mode {
+    /// download the puzzle input into
+    /// `input1.txt` and `input2.txt`
+    #[default]
+    Input,
+    /// extract the examples of the puzzle page
+    /// into `example1.txt` and `example2.txt`
+    Examples,
+    /// submit an answer and record the verdict in
+    /// the day's `answers.toml`
+    Submit {
+        /// puzzle part, 1 or 2
+        #[clap(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        answer: String,
+    },
+}
+
+#[derive(Debug)]
+enum Error {
+    MissingSession,
+    /// the puzzle is not unlocked yet
+    NotFound(String),
+    /// the session cookie was refused
+    BadRequest(String),
+    Status(String, StatusCode),
+    Http(reqwest::Error),
+    Io(PathBuf, std::io::Error),
+    NoExamples,
+    Toml(PathBuf, toml::de::Error),
+    /// the answer was not sent, see the reason
+    Refused(String),
+    /// the part is locked or already solved
+    WrongLevel,
+    UnknownResponse,
+}
+
+impl fmt::Display for Error {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Error::MissingSession => write!(
+                f,
+                "`SESSION` is not set, copy the `session` \
+                 cookie of adventofcode.com into `.env`"
+            ),
+            Error::NotFound(url) => write!(
+                f,
+                "`{url}` was not found (404), the puzzle \
+                 is probably not unlocked yet"
+            ),
+            Error::BadRequest(url) => write!(
+                f,
+                "`{url}` was refused (400), the `SESSION` \
+                 cookie is invalid or expired"
+            ),
+            Error::Status(url, status) => {
+                write!(f, "`{url}` answered {status}")
+            }
+            Error::Http(err) => {
+                write!(f, "request failed: {err}")
+            }
+            Error::Io(path, err) => {
+                write!(f, "{}: {err}", path.display())
+            }
+            Error::NoExamples => write!(
+                f,
+                "no `<pre><code>` example found on the \
+                 puzzle page"
+            ),
+            Error::Toml(path, err) => {
+                write!(f, "{}: {err}", path.display())
+            }
+            Error::Refused(reason) => {
+                write!(f, "answer not submitted, {reason}")
+            }
+            Error::WrongLevel => write!(
+                f,
+                "the site does not expect an answer for \
+                 this part, is it already solved or still \
+                 locked?"
+            ),
+            Error::UnknownResponse => write!(
+                f,
+                "could not find a verdict in the response"
+            ),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
 }
 
 fn parse_day(input: &str) -> IResult<&str, u32> {
     preceded(tag("day-"), complete::u32)(input)
 }
 
-fn main() -> Result<(), reqwest::Error> {
-    let session = std::env::var("SESSION")
-        .expect("should have a session token set");
-    let args = Args::parse();
+/// Downloads `url` with the session cookie,
+/// mapping the status codes the site uses for
+/// locked puzzles and bad cookies to their own
+/// errors.
+fn fetch(
+    client: &Client,
+    url: &str,
+    session: Option<&str>,
+) -> Result<String, Error> {
+    let session = session.ok_or(Error::MissingSession)?;
+    println!("sending to `{}`", url);
+
+    let response = client
+        .get(url)
+        .header(COOKIE, format!("session={session}"))
+        .send()?;
+
+    match response.status() {
+        StatusCode::OK => Ok(response.text()?),
+        StatusCode::NOT_FOUND => {
+            Err(Error::NotFound(url.to_string()))
+        }
+        StatusCode::BAD_REQUEST => {
+            Err(Error::BadRequest(url.to_string()))
+        }
+        status => {
+            Err(Error::Status(url.to_string(), status))
+        }
+    }
+}
+
+/// Reads `cache_file` when present, otherwise
+/// downloads `url` and stores the body there if
+/// `keep` accepts it.
+fn cached_fetch(
+    client: &Client,
+    url: &str,
+    session: Option<&str>,
+    cache_file: &Path,
+    keep: impl Fn(&str) -> bool,
+) -> Result<String, Error> {
+    if let Ok(body) = fs::read_to_string(cache_file) {
+        println!(
+            "using cached `{}`",
+            cache_file.display()
+        );
+        return Ok(body);
+    }
+
+    let body = fetch(client, url, session)?;
+    if keep(&body) {
+        write_file(cache_file, &body)?;
+    }
+    Ok(body)
+}
+
+fn write_file(
+    path: &Path,
+    contents: &str,
+) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::Io(parent.into(), err))?;
+    }
+    fs::write(path, contents)
+        .map_err(|err| Error::Io(path.into(), err))
+}
+
+/// First `<pre><code>` block of each part of the
+/// puzzle description, with tags removed and
+/// entities decoded.
+fn extract_examples(html: &str) -> Vec<Option<String>> {
+    html.split("<article")
+        .skip(1)
+        .map(|article| {
+            let article = article
+                .split("</article>")
+                .next()
+                .unwrap_or_default();
+            let (_, code) =
+                article.split_once("<pre><code>")?;
+            let (code, _) =
+                code.split_once("</code></pre>")?;
+            Some(decode_html(code))
+        })
+        .collect()
+}
+
+fn decode_html(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Outcome of a submission as reported by the
+/// site.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(tag = "verdict", rename_all = "kebab-case")]
+enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// seconds to wait before the next submission
+    RateLimited {
+        wait: u64,
+    },
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::TooHigh => {
+                f.write_str("wrong, too high")
+            }
+            Verdict::TooLow => {
+                f.write_str("wrong, too low")
+            }
+            Verdict::Wrong => f.write_str("wrong"),
+            Verdict::RateLimited { wait } => write!(
+                f,
+                "rate limited, wait {:?}",
+                Duration::from_secs(*wait)
+            ),
+        }
+    }
+}
+
+/// Reads the verdict out of the page returned
+/// after posting an answer.
+fn parse_verdict(html: &str) -> Result<Verdict, Error> {
+    let text = decode_html(html);
+    if text.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if text.contains("your answer is too high") {
+        Ok(Verdict::TooHigh)
+    } else if text.contains("your answer is too low") {
+        Ok(Verdict::TooLow)
+    } else if text.contains("That's not the right answer") {
+        Ok(Verdict::Wrong)
+    } else if text
+        .contains("You gave an answer too recently")
+    {
+        // "You have 1m 5s left to wait."
+        let wait = text
+            .split_once("You have ")
+            .and_then(|(_, rest)| {
+                rest.split_once(" left to wait")
+            })
+            .map(|(wait, _)| {
+                wait.split_whitespace()
+                    .filter_map(|part| {
+                        let (value, unit) = part.split_at(
+                            part.len().saturating_sub(1),
+                        );
+                        let value =
+                            value.parse::<u64>().ok()?;
+                        match unit {
+                            "h" => Some(value * 3600),
+                            "m" => Some(value * 60),
+                            "s" => Some(value),
+                            _ => None,
+                        }
+                    })
+                    .sum()
+            })
+            .unwrap_or(60);
+        Ok(Verdict::RateLimited { wait })
+    } else if text.contains("solving the right level") {
+        Err(Error::WrongLevel)
+    } else {
+        Err(Error::UnknownResponse)
+    }
+}
+
+/// One submission, as stored in `answers.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Attempt {
+    part: u8,
+    answer: String,
+    #[serde(flatten)]
+    verdict: Verdict,
+    /// seconds since the unix epoch
+    timestamp: u64,
+}
+
+/// Content of a day's `answers.toml`: the
+/// accepted answer of each part and every attempt
+/// made.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Answers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+    #[serde(
+        default,
+        rename = "attempt",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    attempts: Vec<Attempt>,
+}
+
+impl Answers {
+    fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|err| {
+                    Error::Toml(path.into(), err)
+                }),
+            Err(err)
+                if err.kind()
+                    == std::io::ErrorKind::NotFound =>
+            {
+                Ok(Answers::default())
+            }
+            Err(err) => Err(Error::Io(path.into(), err)),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let content = toml::to_string(self)
+            .expect("answers should serialize to toml");
+        write_file(path, &content)
+    }
+
+    fn solution(&self, part: u8) -> Option<&String> {
+        match part {
+            1 => self.part1.as_ref(),
+            _ => self.part2.as_ref(),
+        }
+    }
+
+    /// Refuses answers that cannot be right given
+    /// the previous attempts, so they do not cost
+    /// a submission delay.
+    fn check(
+        &self,
+        part: u8,
+        answer: &str,
+    ) -> Result<(), Error> {
+        if let Some(solution) = self.solution(part) {
+            return Err(Error::Refused(format!(
+                "part {part} was already solved with \
+                 `{solution}`"
+            )));
+        }
+
+        let value = answer.trim().parse::<i128>().ok();
+        for attempt in self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.part == part)
+        {
+            let known_wrong = !matches!(
+                attempt.verdict,
+                Verdict::RateLimited { .. }
+            );
+            if known_wrong && attempt.answer == answer {
+                return Err(Error::Refused(format!(
+                    "`{answer}` was already rejected ({})",
+                    attempt.verdict
+                )));
+            }
+
+            let bound = attempt.answer.parse::<i128>().ok();
+            let out_of_bounds = match (value, bound) {
+                (Some(value), Some(bound)) => {
+                    match attempt.verdict {
+                        Verdict::TooHigh => value >= bound,
+                        Verdict::TooLow => value <= bound,
+                        _ => false,
+                    }
+                }
+                _ => false,
+            };
+            if out_of_bounds {
+                return Err(Error::Refused(format!(
+                    "`{}` was {}",
+                    attempt.answer, attempt.verdict
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record(
+        &mut self,
+        part: u8,
+        answer: &str,
+        verdict: Verdict,
+    ) {
+        if verdict == Verdict::Correct {
+            match part {
+                1 => self.part1 = Some(answer.to_string()),
+                _ => self.part2 = Some(answer.to_string()),
+            }
+        }
+        self.attempts.push(Attempt {
+            part,
+            answer: answer.to_string(),
+            verdict,
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        });
+    }
+}
+
+/// Posts an answer and reads the verdict.
+fn submit(
+    client: &Client,
+    url: &str,
+    session: Option<&str>,
+    part: u8,
+    answer: &str,
+) -> Result<Verdict, Error> {
+    let session = session.ok_or(Error::MissingSession)?;
+    println!("sending `{answer}` to `{url}`");
+
+    let response = client
+        .post(url)
+        .header(COOKIE, format!("session={session}"))
+        .form(&[
+            ("level", part.to_string()),
+            ("answer", answer.to_string()),
+        ])
+        .send()?;
+
+    match response.status() {
+        StatusCode::OK => parse_verdict(&response.text()?),
+        StatusCode::NOT_FOUND => {
+            Err(Error::NotFound(url.to_string()))
+        }
+        StatusCode::BAD_REQUEST => {
+            Err(Error::BadRequest(url.to_string()))
+        }
+        status => {
+            Err(Error::Status(url.to_string(), status))
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), Error> {
     let Ok((_, day)) = parse_day(&args.day) else {
         let mut cmd = Args::command();
         cmd.error(
@@ -53,31 +547,473 @@ fn main() -> Result<(), reqwest::Error> {
         .exit()
     };
 
-    let url = format!(
-        "https://adventofcode.com/2024/day/{day}/input"
-    );
-    println!("sending to `{}`", url);
-
+    let session = std::env::var("SESSION").ok();
     let client = Client::new();
-    let input_data = client
-        .get(url)
-        .header(COOKIE, format!("session={session}"))
-        .send()?
-        .text()?;
-
-    for filename in ["input1.txt", "input2.txt"] {
-        let file_path = args
-            .current_working_directory
-            .join(&args.day)
-            .join(filename);
-        let mut file = File::create(&file_path)
-            .expect("should be able to create a file");
-
-        file.write_all(input_data.as_bytes()).expect(
-            "should be able to write to input file",
-        );
-        println!("wrote {}", file_path.display());
+    let base_url = args.base_url.trim_end_matches('/');
+    let cache_dir = args
+        .cache_dir
+        .clone()
+        .unwrap_or_else(|| {
+            args.current_working_directory
+                .join(".aoc-cache")
+        })
+        .join(args.year.to_string())
+        .join(&args.day);
+    let day_dir =
+        args.current_working_directory.join(&args.day);
+
+    match args.mode.clone().unwrap_or_default() {
+        Mode::Input => {
+            let input_data = cached_fetch(
+                &client,
+                &format!(
+                    "{base_url}/{}/day/{day}/input",
+                    args.year
+                ),
+                session.as_deref(),
+                &cache_dir.join("input.txt"),
+                |_| true,
+            )?;
+
+            for filename in ["input1.txt", "input2.txt"] {
+                let file_path = day_dir.join(filename);
+                write_file(&file_path, &input_data)?;
+                println!("wrote {}", file_path.display());
+            }
+        }
+        Mode::Examples => {
+            // the page only shows part 2 once part 1
+            // is solved, so keep it only when complete
+            let html = cached_fetch(
+                &client,
+                &format!(
+                    "{base_url}/{}/day/{day}",
+                    args.year
+                ),
+                session.as_deref(),
+                &cache_dir.join("puzzle.html"),
+                |html| extract_examples(html).len() >= 2,
+            )?;
+
+            let examples = extract_examples(&html);
+            let Some(Some(example1)) = examples.first()
+            else {
+                return Err(Error::NoExamples);
+            };
+            let mut files =
+                vec![("example1.txt", example1)];
+            match examples.get(1) {
+                Some(example2) => files.push((
+                    "example2.txt",
+                    example2.as_ref().unwrap_or(example1),
+                )),
+                None => println!(
+                    "part 2 is not unlocked yet, skipping \
+                     example2.txt"
+                ),
+            }
+
+            for (filename, example) in files {
+                let file_path = day_dir.join(filename);
+                write_file(&file_path, example)?;
+                println!("wrote {}", file_path.display());
+            }
+        }
+        Mode::Submit { part, answer } => {
+            let answer = answer.trim();
+            let answers_path = day_dir.join("answers.toml");
+            let mut answers = Answers::load(&answers_path)?;
+            answers.check(part, answer)?;
+
+            let verdict = submit(
+                &client,
+                &format!(
+                    "{base_url}/{}/day/{day}/answer",
+                    args.year
+                ),
+                session.as_deref(),
+                part,
+                answer,
+            )?;
+
+            answers.record(part, answer, verdict);
+            answers.save(&answers_path)?;
+            println!(
+                "part {part}: `{answer}` is {verdict}"
+            );
+        }
     }
 
     Ok(())
 }
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    const PUZZLE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1 ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+<em>4</em>   3
+</code></pre>
+<pre><code>not the example</code></pre>
+</article>
+<p>Your puzzle answer was <code>11</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>a &lt;b&gt; &amp; c
+</code></pre>
+</article>
+</main>"#;
+
+    /// Minimal HTTP server answering like the
+    /// site: `session=bad` gets a 400, unknown
+    /// paths a 404. Posted answers are judged
+    /// against 11, with 99 hitting the rate
+    /// limit. Returns its url and the number of
+    /// requests served so far.
+    fn stand_in_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .expect("should bind a local port");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().unwrap()
+        );
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut reader = BufReader::new(&stream);
+                let mut path = String::new();
+                let mut bad_cookie = false;
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line =
+                        line.trim_end().to_lowercase();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((_, request)) =
+                        line.split_once(' ').filter(|_| {
+                            line.starts_with("get ")
+                                || line.starts_with("post ")
+                        })
+                    {
+                        path = request
+                            .split(' ')
+                            .next()
+                            .unwrap()
+                            .to_string();
+                    }
+                    if let Some(length) = line
+                        .strip_prefix("content-length: ")
+                    {
+                        content_length =
+                            length.parse().unwrap();
+                    }
+                    bad_cookie |= line
+                        .starts_with("cookie: session=bad");
+                }
+                let mut form = vec![0; content_length];
+                reader.read_exact(&mut form).unwrap();
+                let form = String::from_utf8(form).unwrap();
+                let answer = form
+                    .split('&')
+                    .find_map(|field| {
+                        field.strip_prefix("answer=")
+                    })
+                    .and_then(|answer| {
+                        answer.parse::<u64>().ok()
+                    });
+
+                let (status, body) = match path.as_str() {
+                    _ if bad_cookie => {
+                        ("400 Bad Request", "")
+                    }
+                    "/2023/day/1/input" => {
+                        ("200 OK", "1 2\n")
+                    }
+                    "/2023/day/1" => ("200 OK", PUZZLE),
+                    "/2023/day/1/answer" => {
+                        ("200 OK", verdict_page(answer))
+                    }
+                    _ => ("404 Not Found", ""),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: \
+                     {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    /// Page the site returns for a posted answer
+    /// when the right one is 11.
+    fn verdict_page(answer: Option<u64>) -> &'static str {
+        match answer {
+            Some(11) => {
+                "<p>That's the right answer! You are \
+                 one gold star closer.</p>"
+            }
+            Some(99) => {
+                "<p>You gave an answer too recently. You \
+                 have 1m 5s left to wait.</p>"
+            }
+            Some(20..) => {
+                "<p>That's not the right answer; your \
+                 answer is too high.</p>"
+            }
+            Some(..5) => {
+                "<p>That's not the right answer; your \
+                 answer is too low.</p>"
+            }
+            _ => "<p>That's not the right answer.</p>",
+        }
+    }
+
+    fn args(
+        dir: &Path,
+        base_url: &str,
+        day: &str,
+        mode: Mode,
+    ) -> Args {
+        Args {
+            day: day.to_string(),
+            year: 2023,
+            current_working_directory: dir.into(),
+            base_url: base_url.to_string(),
+            cache_dir: None,
+            mode: Some(mode),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "get-aoc-input-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_extract_examples() {
+        assert_eq!(
+            vec![
+                Some("3   4\n4   3\n".to_string()),
+                Some("a <b> & c\n".to_string()),
+            ],
+            extract_examples(PUZZLE)
+        );
+    }
+
+    #[test]
+    fn test_decode_html() {
+        assert_eq!(
+            "<a> & \"b\" 'c' 'd' 'e' &apos;",
+            decode_html(
+                "<em>&lt;a&gt;</em> &amp; &quot;b&quot; \
+                 &#39;c&#39; &apos;d&apos; &#x27;e&#x27; \
+                 &amp;apos;"
+            )
+        );
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (url, _) = stand_in_server();
+        let client = Client::new();
+
+        assert!(matches!(
+            fetch(
+                &client,
+                &format!("{url}/2023/day/1/input"),
+                None
+            ),
+            Err(Error::MissingSession)
+        ));
+        assert!(matches!(
+            fetch(
+                &client,
+                &format!("{url}/2023/day/25/input"),
+                Some("good")
+            ),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            fetch(
+                &client,
+                &format!("{url}/2023/day/1/input"),
+                Some("bad")
+            ),
+            Err(Error::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_input_is_cached() {
+        let (url, requests) = stand_in_server();
+        let dir = temp_dir("input");
+        let args = args(&dir, &url, "day-01", Mode::Input);
+        std::env::set_var("SESSION", "good");
+
+        run(&args).unwrap();
+        run(&args).unwrap();
+
+        assert_eq!(1, requests.load(Ordering::SeqCst));
+        assert_eq!(
+            "1 2\n",
+            fs::read_to_string(
+                dir.join("day-01/input2.txt")
+            )
+            .unwrap()
+        );
+        assert!(dir
+            .join(".aoc-cache/2023/day-01/input.txt")
+            .exists());
+    }
+
+    #[test]
+    fn test_examples() {
+        let (url, _) = stand_in_server();
+        let dir = temp_dir("examples");
+        std::env::set_var("SESSION", "good");
+
+        run(&args(
+            &dir,
+            &url,
+            "day-01",
+            Mode::Examples,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            "a <b> & c\n",
+            fs::read_to_string(
+                dir.join("day-01/example2.txt")
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            run(&args(
+                &dir,
+                &url,
+                "day-02",
+                Mode::Examples
+            )),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            Verdict::RateLimited { wait: 3725 },
+            parse_verdict(
+                "You gave an answer too recently. You \
+                 have 1h 2m 5s left to wait."
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            parse_verdict(
+                "You don&apos;t seem to be solving the \
+                 right level."
+            ),
+            Err(Error::WrongLevel)
+        ));
+        assert!(matches!(
+            parse_verdict("<html></html>"),
+            Err(Error::UnknownResponse)
+        ));
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = stand_in_server();
+        let dir = temp_dir("submit");
+        std::env::set_var("SESSION", "good");
+        let submit = |answer: &str| {
+            run(&args(
+                &dir,
+                &url,
+                "day-01",
+                Mode::Submit {
+                    part: 1,
+                    answer: answer.to_string(),
+                },
+            ))
+        };
+
+        submit("99").unwrap();
+        submit("7").unwrap();
+        submit("20").unwrap();
+        submit("4").unwrap();
+        assert_eq!(4, requests.load(Ordering::SeqCst));
+
+        // rejected before reaching the site
+        for answer in ["7", "25", "3", "4"] {
+            assert!(matches!(
+                submit(answer),
+                Err(Error::Refused(_))
+            ));
+        }
+        assert_eq!(4, requests.load(Ordering::SeqCst));
+
+        submit("11").unwrap();
+        assert!(matches!(
+            submit("12"),
+            Err(Error::Refused(_))
+        ));
+
+        let answers =
+            Answers::load(&dir.join("day-01/answers.toml"))
+                .unwrap();
+        assert_eq!(Some("11"), answers.part1.as_deref());
+        assert_eq!(None, answers.part2);
+        assert_eq!(
+            vec![
+                Verdict::RateLimited { wait: 65 },
+                Verdict::Wrong,
+                Verdict::TooHigh,
+                Verdict::TooLow,
+                Verdict::Correct,
+            ],
+            answers
+                .attempts
+                .iter()
+                .map(|attempt| attempt.verdict)
+                .collect::<Vec<_>>()
+        );
+    }
+}